@@ -0,0 +1,83 @@
+//! # Canonical
+//!
+//! The canonical form of RFC 8785 (JCS), so the same value always serializes to the same bytes
+//! and can be hashed or signed.
+
+use crate::prelude::*;
+use crate::escape::{self, Escape};
+use crate::float;
+use crate::json::JSON;
+use crate::just::TokenError;
+
+impl JSON {
+    /// Writes the value in the canonical form of RFC 8785: no whitespace, object keys sorted by
+    /// their UTF-16 code units, numbers formatted like ECMAScript and only the escapes JSON requires.
+    /// Numbers are written as the `f64` closest to them, the same as every other JCS implementation.
+    /// Fails on `NaN` and infinities, which JSON has no way to write.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::str::FromStr;
+    /// # use json_parser::json::JSON;
+    /// let json = JSON::from_str(r#"{"b": [1E3, 0.10, "€\/"], "a": null}"#).unwrap();
+    /// assert_eq!(json.to_canonical_string().unwrap(), r#"{"a":null,"b":[1000,0.1,"€/"]}"#);
+    /// assert!(JSON::Float(f64::NAN).to_canonical_string().is_err());
+    /// ```
+    pub fn to_canonical_string(&self) -> Result<String, TokenError> {
+        let mut canonical = String::new();
+        write_value(&mut canonical, self)?;
+        Ok(canonical)
+    }
+}
+
+fn write_value(out: &mut String, json: &JSON) -> Result<(), TokenError> {
+    match *json {
+        JSON::Boolean(boolean) => out.push_str(if boolean { "true" } else { "false" }),
+        JSON::Integer(int_val) => write_number(out, int_val as f64)?,
+        JSON::Unsigned(uint_val) => write_number(out, uint_val as f64)?,
+        JSON::Float(float) => write_number(out, float)?,
+        JSON::Number(ref number) => match number.as_f64() {
+            Some(float) => write_number(out, float)?,
+            None => return Err(TokenError::Msg(format!("`{}` is too big for a canonical number", number)))
+        },
+        JSON::String(ref string) => escape::write(out, string, Escape::Minimal).expect("writing to a String can't fail"),
+        JSON::Array(ref json_arr) => {
+            out.push('[');
+            for (i, item) in json_arr.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_value(out, item)?;
+            }
+            out.push(']');
+        },
+        JSON::Object(ref json_obj) => {
+            let mut members: Vec<(&String, &JSON)> = json_obj.iter().collect();
+            members.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+
+            out.push('{');
+            for (i, (key, value)) in members.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                escape::write(out, key, Escape::Minimal).expect("writing to a String can't fail");
+                out.push(':');
+                write_value(out, value)?;
+            }
+            out.push('}');
+        },
+        JSON::Null => out.push_str("null")
+    }
+
+    Ok(())
+}
+
+fn write_number(out: &mut String, float: f64) -> Result<(), TokenError> {
+    if !float.is_finite() {
+        return Err(TokenError::Msg(format!("`{}` can't be written as JSON", float)))
+    }
+
+    float::write(out, float, false);
+    Ok(())
+}
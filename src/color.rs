@@ -0,0 +1,75 @@
+//! # Color
+//!
+//! Pretty printing with ANSI colors for terminals, the way `jq -C` prints.
+
+use crate::json::JSON;
+use crate::writer::{JSONWriter, NonFinite};
+
+/// The ANSI SGR parameters each part of the output is colored with, like `"34;1"` for bold blue.
+/// An empty string leaves that part uncolored. The default is the scheme `jq` uses.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColorScheme {
+    pub key: String,
+    pub string: String,
+    pub number: String,
+    pub boolean: String,
+    pub null: String,
+
+    /// Brackets and braces of arrays and objects
+    pub bracket: String,
+}
+
+impl ColorScheme {
+    /// A scheme that colors nothing, for when the output isn't going to a terminal
+    pub fn none() -> Self {
+        Self {
+            key: String::new(),
+            string: String::new(),
+            number: String::new(),
+            boolean: String::new(),
+            null: String::new(),
+            bracket: String::new()
+        }
+    }
+}
+
+impl Default for ColorScheme {
+    fn default() -> Self {
+        Self {
+            key: "34;1".to_string(),
+            string: "0;32".to_string(),
+            number: "0;39".to_string(),
+            boolean: "0;39".to_string(),
+            null: "1;30".to_string(),
+            bracket: "1;39".to_string()
+        }
+    }
+}
+
+impl JSON {
+    /// Pretty prints the value indented by two spaces, colored with `colors`.
+    /// `NaN` and infinities are written as `null`, the same as `Display` writes them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use json_parser::{array, object};
+    /// # use json_parser::color::ColorScheme;
+    /// let json = object!{"ok" => array![true, 1]};
+    /// let colors = ColorScheme {
+    ///     number: "31".to_string(),
+    ///     ..ColorScheme::none()
+    /// };
+    /// assert_eq!(json.to_colored_string(&colors), "{\n  \"ok\": [\n    true,\n    \x1b[31m1\x1b[0m\n  ]\n}");
+    /// ```
+    pub fn to_colored_string(&self, colors: &ColorScheme) -> String {
+        let mut writer = JSONWriter::new(Vec::new())
+            .pretty(2)
+            .non_finite(NonFinite::Null)
+            .colors(colors.clone());
+        writer.json(self).expect("a whole value always fits an empty writer");
+
+        let bytes = writer.finish().expect("writing to a Vec can't fail");
+        String::from_utf8(bytes).expect("the writer only writes UTF-8")
+    }
+}
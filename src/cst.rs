@@ -0,0 +1,523 @@
+//! # CST
+//!
+//! A lossless concrete syntax tree. It keeps every token, space and comment of the source,
+//! so a document can be edited at a path and printed back without reformatting the rest.
+
+use core::fmt;
+use core::mem;
+use core::str::FromStr;
+
+use crate::prelude::*;
+use crate::json::JSON;
+use crate::just::{self, Cursor, Lexeme, Mode, Span, Token, TokenError, Tokenize};
+
+/// A parsed document that prints back exactly as it was read
+///
+/// # Example
+///
+/// ```
+/// # use json_parser::cst::CST;
+/// let mut cst = CST::from_jsonc(r#"{
+///     // Bumped by the release script
+///     "version": "1.0.0",
+///     "name":    "json_parser"
+/// }"#).unwrap();
+///
+/// cst.set("version", "1.1.0").unwrap();
+/// assert_eq!(cst.to_string(), r#"{
+///     // Bumped by the release script
+///     "version": "1.1.0",
+///     "name":    "json_parser"
+/// }"#);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct CST {
+    leading: String,
+    root: Node,
+    trailing: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    /// A string, number, boolean or null exactly as it was written
+    Scalar(String),
+
+    /// The entries and the trivia between the last entry (or `[`) and `]`
+    Array(Vec<Entry>, String),
+
+    /// The entries and the trivia between the last entry (or `{`) and `}`
+    Object(Vec<Entry>, String),
+}
+
+/// One array element or object member, with the trivia around it
+#[derive(Debug, Clone, PartialEq)]
+struct Entry {
+    prefix: String,
+    key: Option<Key>,
+    value: Node,
+    suffix: String,
+    comma: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Key {
+    raw: String,
+    name: String,
+    before_colon: String,
+    after_colon: String,
+}
+
+#[derive(Debug)]
+pub enum EditError {
+    NotFound(String),
+    AlreadyExists(String),
+    NotAContainer(String),
+    TooDeep(String),
+}
+
+struct Builder<'a> {
+    source: &'a str,
+    lexemes: Vec<Lexeme<'a>>,
+    position: usize,
+    depth: usize,
+    mode: Mode,
+}
+
+impl CST {
+    /// Parses JSONC, keeping its comments so they survive edits
+    pub fn from_jsonc(s: &str) -> Result<CST, TokenError> {
+        CST::parse_with_mode(s, Mode::JSONC)
+    }
+
+    fn parse_with_mode(s: &str, mode: Mode) -> Result<CST, TokenError> {
+        let mut builder = Builder {
+            source: s,
+            lexemes: Cursor::new(s).lexemes(mode)?,
+            position: 0,
+            depth: 0,
+            mode
+        };
+
+        let leading = builder.trivia();
+        let root = match builder.next_token() {
+            Some((token, span)) => builder.parse_value(token, span)?,
+            None => return Err(TokenError::Msg("Could not tokenize JSON".to_string()))
+        };
+        let trailing = builder.trivia();
+        if let Some((token, _)) = builder.next_token() {
+            return Err(TokenError::Msg(format!("Unexpected Value after end of document -> `{:?}`", token)))
+        }
+
+        Ok(CST {
+            leading,
+            root,
+            trailing
+        })
+    }
+
+    /// Converts the tree into a `JSON` value, dropping the formatting
+    pub fn to_json(&self) -> Result<JSON, TokenError> {
+        JSON::from_jsonc(&self.to_string())
+    }
+
+    /// Replaces the value at a dot separated path like `pageInfo.totalResults` or `items.2`.
+    /// A missing key is added to the end of its object.
+    pub fn set<'a, S, V>(&mut self, path: S, value: V) -> Result<(), EditError>
+    where
+        S: Into<&'a str>,
+        V: Into<JSON>
+    {
+        let path = path.into();
+        let value = node_from_json(&value.into(), path)?;
+        let (parent, last) = self.parent_mut(path)?;
+        match parent {
+            Node::Object(entries, trailing) => {
+                match entries.iter_mut().rev().find(|entry| entry.has_key(last)) {
+                    Some(entry) => entry.value = value,
+                    None => append(entries, trailing, Entry::member(last, value))
+                }
+                Ok(())
+            },
+            Node::Array(entries, _) => {
+                match index(last).and_then(|index| entries.get_mut(index)) {
+                    Some(entry) => {
+                        entry.value = value;
+                        Ok(())
+                    },
+                    None => Err(EditError::NotFound(path.to_string()))
+                }
+            },
+            Node::Scalar(_) => Err(EditError::NotAContainer(path.to_string()))
+        }
+    }
+
+    /// Adds a new member to an object, or a new element to an array at the given index,
+    /// shifting the elements after it.
+    pub fn insert<'a, S, V>(&mut self, path: S, value: V) -> Result<(), EditError>
+    where
+        S: Into<&'a str>,
+        V: Into<JSON>
+    {
+        let path = path.into();
+        let value = node_from_json(&value.into(), path)?;
+        let (parent, last) = self.parent_mut(path)?;
+        match parent {
+            Node::Object(entries, trailing) => {
+                if entries.iter().any(|entry| entry.has_key(last)) {
+                    return Err(EditError::AlreadyExists(path.to_string()))
+                }
+                append(entries, trailing, Entry::member(last, value));
+                Ok(())
+            },
+            Node::Array(entries, trailing) => {
+                match index(last) {
+                    Some(index) if index == entries.len() => {
+                        append(entries, trailing, Entry::element(value));
+                        Ok(())
+                    },
+                    Some(index) if index < entries.len() => {
+                        let mut entry = Entry::element(value);
+                        entry.prefix = entries[index].prefix.clone();
+                        entry.comma = true;
+                        if index == 0 {
+                            entries[0].prefix = match entries.get(1) {
+                                Some(next) => next.prefix.clone(),
+                                None => " ".to_string()
+                            };
+                        }
+                        entries.insert(index, entry);
+                        Ok(())
+                    },
+                    _ => Err(EditError::NotFound(path.to_string()))
+                }
+            },
+            Node::Scalar(_) => Err(EditError::NotAContainer(path.to_string()))
+        }
+    }
+
+    /// Removes an object member or array element, along with the comments in front of it.
+    /// Every copy of a repeated key is removed.
+    pub fn remove<'a, S>(&mut self, path: S) -> Result<(), EditError>
+    where
+        S: Into<&'a str>
+    {
+        let path = path.into();
+        let (parent, last) = self.parent_mut(path)?;
+        match parent {
+            Node::Object(entries, trailing) => {
+                let mut removed = false;
+                while let Some(position) = entries.iter().rposition(|entry| entry.has_key(last)) {
+                    remove_entry(entries, trailing, position);
+                    removed = true;
+                }
+                match removed {
+                    true => Ok(()),
+                    false => Err(EditError::NotFound(path.to_string()))
+                }
+            },
+            Node::Array(entries, trailing) => {
+                match index(last).filter(|index| *index < entries.len()) {
+                    Some(position) => {
+                        remove_entry(entries, trailing, position);
+                        Ok(())
+                    },
+                    None => Err(EditError::NotFound(path.to_string()))
+                }
+            },
+            Node::Scalar(_) => Err(EditError::NotAContainer(path.to_string()))
+        }
+    }
+
+    fn parent_mut<'p>(&mut self, path: &'p str) -> Result<(&mut Node, &'p str), EditError> {
+        let mut segments: Vec<&str> = path.split('.').collect();
+        let last = match segments.pop() {
+            Some(last) if !last.is_empty() => last,
+            _ => return Err(EditError::NotFound(path.to_string()))
+        };
+
+        let mut node = &mut self.root;
+        for segment in segments {
+            let entry = match node {
+                Node::Object(entries, _) => entries.iter_mut().rev().find(|entry| entry.has_key(segment)),
+                Node::Array(entries, _) => index(segment).and_then(move |index| entries.get_mut(index)),
+                Node::Scalar(_) => return Err(EditError::NotAContainer(path.to_string()))
+            };
+
+            node = match entry {
+                Some(entry) => &mut entry.value,
+                None => return Err(EditError::NotFound(path.to_string()))
+            };
+        }
+
+        Ok((node, last))
+    }
+}
+
+impl Entry {
+    fn element(value: Node) -> Self {
+        Self {
+            prefix: String::new(),
+            key: None,
+            value,
+            suffix: String::new(),
+            comma: false
+        }
+    }
+
+    fn member(name: &str, value: Node) -> Self {
+        let mut entry = Entry::element(value);
+        entry.key = Some(Key {
+            raw: JSON::from(name).to_string(),
+            name: name.to_string(),
+            before_colon: String::new(),
+            after_colon: " ".to_string()
+        });
+        entry
+    }
+
+    fn has_key(&self, name: &str) -> bool {
+        match self.key {
+            Some(ref key) => key.name == name,
+            None => false
+        }
+    }
+}
+
+/// Puts an entry at the end, copying the layout of the entry before it
+fn append(entries: &mut Vec<Entry>, trailing: &mut String, mut entry: Entry) {
+    match entries.last_mut() {
+        Some(last) => {
+            entry.prefix = last.prefix.clone();
+            entry.suffix = mem::take(&mut last.suffix);
+            entry.comma = last.comma;
+            last.comma = true;
+            if let (Some(key), Some(last_key)) = (entry.key.as_mut(), last.key.as_ref()) {
+                key.before_colon = last_key.before_colon.clone();
+                key.after_colon = last_key.after_colon.clone();
+            }
+        },
+        None => entry.suffix = mem::take(trailing)
+    }
+
+    entries.push(entry);
+}
+
+/// Takes out an entry, handing its comma and trailing comments to the entry before it
+fn remove_entry(entries: &mut Vec<Entry>, trailing: &mut String, position: usize) {
+    let removed = entries.remove(position);
+    if position == entries.len() {
+        match entries.last_mut() {
+            Some(last) => {
+                if !removed.comma {
+                    last.comma = false;
+                    last.suffix.push_str(&removed.suffix);
+                }
+            },
+            None => trailing.insert_str(0, &removed.suffix)
+        }
+    } else if position == 0 && !removed.prefix.contains('\n') {
+        entries[0].prefix = removed.prefix;
+    }
+}
+
+fn index(segment: &str) -> Option<usize> {
+    segment.parse::<usize>().ok()
+}
+
+/// Builds the node for a value going to `path`, which can't take the document past `MAX_DEPTH`
+fn node_from_json(value: &JSON, path: &str) -> Result<Node, EditError> {
+    let source = value.to_string();
+    let mut builder = Builder {
+        source: &source,
+        lexemes: Cursor::new(&source).lexemes(Mode::Strict).expect("JSON should always print valid JSON"),
+        position: 0,
+        depth: path.split('.').count(),
+        mode: Mode::Strict
+    };
+
+    match builder.next_token() {
+        Some((token, span)) => builder.parse_value(token, span).map_err(|_| EditError::TooDeep(path.to_string())),
+        None => unreachable!("JSON should always print a value")
+    }
+}
+
+impl<'a> Builder<'a> {
+    /// Collects the whitespace and comments up to the next token
+    fn trivia(&mut self) -> String {
+        let mut trivia = String::new();
+        while let Some(Lexeme::Whitespace(span) | Lexeme::Comment(span)) = self.lexemes.get(self.position) {
+            trivia.push_str(&self.source[span.start..span.end]);
+            self.position += 1;
+        }
+
+        trivia
+    }
+
+    fn next_token(&mut self) -> Option<(Token<'a>, Span)> {
+        match self.lexemes.get(self.position) {
+            Some(Lexeme::Token(token, span)) => {
+                self.position += 1;
+                Some((token.clone(), *span))
+            },
+            _ => None
+        }
+    }
+
+    fn parse_value(&mut self, token: Token<'a>, span: Span) -> Result<Node, TokenError> {
+        match token {
+            Token::OpeningCurlyBrace | Token::OpeningBracket => {
+                just::check_depth(self.depth)?;
+                self.depth += 1;
+                let node = match token {
+                    Token::OpeningCurlyBrace => self.parse_object(),
+                    _ => self.parse_array()
+                };
+                self.depth -= 1;
+                node
+            },
+            Token::String(_) | Token::Boolean(_) | Token::Integer(_) | Token::Unsigned(_) | Token::Float(_) | Token::Null =>
+                Ok(Node::Scalar(self.source[span.start..span.end].to_string())),
+            _ => Err(TokenError::Msg(format!("Unexpected Value -> `{:?}`", token)))
+        }
+    }
+
+    fn parse_array(&mut self) -> Result<Node, TokenError> {
+        let mut entries: Vec<Entry> = Vec::new();
+
+        loop {
+            let prefix = self.trivia();
+            let (token, span) = match self.next_token() {
+                Some(token) => token,
+                None => return Err(TokenError::Msg("Expected Value -> `]`".to_string()))
+            };
+
+            if let Token::ClosingBracket = token {
+                if entries.is_empty() || self.mode == Mode::JSONC {
+                    return Ok(Node::Array(entries, prefix))
+                }
+                return Err(TokenError::Msg("Expected Value -> `arr value`".to_string()))
+            }
+
+            let value = self.parse_value(token, span)?;
+            let suffix = self.trivia();
+            let comma = match self.next_token() {
+                Some((Token::Comma, _)) => true,
+                Some((Token::ClosingBracket, _)) => false,
+                _ => return Err(TokenError::Msg("Expected Value -> `[\",\", \"]\"]`".to_string()))
+            };
+
+            entries.push(Entry {
+                prefix,
+                key: None,
+                value,
+                suffix,
+                comma
+            });
+
+            if !comma {
+                return Ok(Node::Array(entries, String::new()))
+            }
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<Node, TokenError> {
+        let mut entries: Vec<Entry> = Vec::new();
+
+        loop {
+            let prefix = self.trivia();
+            let (name, span) = match self.next_token() {
+                Some((Token::String(name), span)) => (name, span),
+                Some((Token::ClosingCurlyBrace, _)) if entries.is_empty() || self.mode == Mode::JSONC =>
+                    return Ok(Node::Object(entries, prefix)),
+                _ => return Err(TokenError::Msg("Expected Value -> `obj keyword`".to_string()))
+            };
+
+            let before_colon = self.trivia();
+            match self.next_token() {
+                Some((Token::Colon, _)) => {},
+                _ => return Err(TokenError::Msg("Expected Value -> `:`".to_string()))
+            }
+
+            let after_colon = self.trivia();
+            let value = match self.next_token() {
+                Some((token, span)) => self.parse_value(token, span)?,
+                None => return Err(TokenError::Msg("Expected Value -> `obj value`".to_string()))
+            };
+
+            let suffix = self.trivia();
+            let comma = match self.next_token() {
+                Some((Token::Comma, _)) => true,
+                Some((Token::ClosingCurlyBrace, _)) => false,
+                _ => return Err(TokenError::Msg("Expected Value -> `[\",\", \"}\"]`".to_string()))
+            };
+
+            entries.push(Entry {
+                prefix,
+                key: Some(Key {
+                    raw: self.source[span.start..span.end].to_string(),
+                    name: name.into_owned(),
+                    before_colon,
+                    after_colon
+                }),
+                value,
+                suffix,
+                comma
+            });
+
+            if !comma {
+                return Ok(Node::Object(entries, String::new()))
+            }
+        }
+    }
+}
+
+impl FromStr for CST {
+    type Err = TokenError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        CST::parse_with_mode(s, Mode::Strict)
+    }
+}
+
+impl fmt::Display for CST {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}{}", self.leading, self.root, self.trailing)
+    }
+}
+
+impl fmt::Display for Node {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Node::Scalar(ref text) => write!(f, "{}", text),
+            Node::Array(ref entries, ref trailing) => {
+                write!(f, "[")?;
+                for entry in entries {
+                    write!(f, "{}", entry)?;
+                }
+                write!(f, "{}]", trailing)
+            },
+            Node::Object(ref entries, ref trailing) => {
+                write!(f, "{{")?;
+                for entry in entries {
+                    write!(f, "{}", entry)?;
+                }
+                write!(f, "{}}}", trailing)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.prefix)?;
+        if let Some(ref key) = self.key {
+            write!(f, "{}{}:{}", key.raw, key.before_colon, key.after_colon)?;
+        }
+        write!(f, "{}{}", self.value, self.suffix)?;
+        if self.comma {
+            write!(f, ",")?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,481 @@
+//! # Document
+//!
+//! A parsed JSON document stored as one flat tape of nodes instead of a tree of boxes,
+//! so parsing allocates a handful of buffers rather than one per value. It's read through
+//! `Element` handles, with `at`, `member`, `get` and iteration instead of `JSON`'s `[]`.
+
+use core::slice;
+
+use crate::prelude::*;
+use crate::json::{Map, JSON};
+use crate::just::{Cursor, Mode, Span, Token, TokenError, JUST, Tokenize};
+use crate::number::Number;
+
+/// Every value of a document, laid out in the order it was written.
+/// A container is followed by its children, and an object's children alternate key, value.
+///
+/// # Example
+///
+/// ```
+/// # use json_parser::{array, json::JSON};
+/// # use json_parser::document::Document;
+/// let document = Document::parse(r#"[
+///     {"id_str": "877994604561387520", "entities": {"hashtags": [{"indices": [103, 111]}]}},
+///     {"id_str": "877994604561387521", "entities": {"hashtags": []}}
+/// ]"#).unwrap();
+///
+/// let root = document.root();
+/// assert_eq!(root.len(), Some(2));
+/// assert_eq!(root.at(0).unwrap().member("id_str").unwrap().get_string(), Some("877994604561387520"));
+/// assert_eq!(document.get("0.entities.hashtags.0.indices"), Some(array![103, 111]));
+///
+/// let ids: Vec<&str> = root.members().unwrap()
+///     .filter_map(|tweet| tweet.member("id_str")?.get_string())
+///     .collect();
+/// assert_eq!(ids, ["877994604561387520", "877994604561387521"]);
+/// assert_eq!(Document::from(&document.to_json()).to_json(), document.to_json());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Document {
+    tape: Vec<Node>,
+    strings: String,
+}
+
+/// One value on the tape. Strings and numbers point into `Document::strings`,
+/// and containers point past the end of their children.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Node {
+    Boolean(bool),
+    Integer(i64),
+    Unsigned(u64),
+    Float(f64),
+    Number(usize, usize),
+    String(usize, usize),
+
+    /// A key that a later copy of it in the same object overrides, left out like `JSON` leaves it out
+    Shadowed(usize, usize),
+    Array { len: usize, end: usize },
+    Object { len: usize, end: usize },
+    Null,
+}
+
+/// A value inside a `Document`, a small handle made whenever one is asked for.
+/// Unlike `JSON` it can't be indexed with `[]`, because `Index` has to return a reference
+/// and there's no stored element for it to point at. `at` and `member` take its place,
+/// returning `None` where `JSON`'s `[]` would panic.
+///
+/// # Example
+///
+/// ```
+/// # use json_parser::document::Document;
+/// # use json_parser::json::JSON;
+/// let source = r#"{"users": [{"name": "ann"}]}"#;
+/// let json: JSON = source.parse().unwrap();
+/// let document = Document::parse(source).unwrap();
+///
+/// let name = document.root().member("users").and_then(|users| users.at(0)).and_then(|user| user.member("name"));
+/// assert_eq!(name.unwrap().get_string(), json["users"][0]["name"].get_string().as_deref());
+/// assert!(document.root().member("groups").is_none());
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Element<'d> {
+    document: &'d Document,
+    index: usize,
+}
+
+/// The items of an array, see `Element::members`
+pub struct Members<'d> {
+    document: &'d Document,
+    next: usize,
+    remaining: usize,
+}
+
+/// The keys and values of an object, see `Element::entries`
+pub struct Entries<'d> {
+    document: &'d Document,
+    next: usize,
+    remaining: usize,
+}
+
+impl Document {
+    /// Parses strict JSON onto a tape
+    pub fn parse(s: &str) -> Result<Document, TokenError> {
+        Document::parse_with_mode(s, Mode::Strict)
+    }
+
+    /// Parses JSONC, JSON that allows `//` and `/* */` comments and trailing commas
+    pub fn from_jsonc(s: &str) -> Result<Document, TokenError> {
+        Document::parse_with_mode(s, Mode::JSONC)
+    }
+
+    fn parse_with_mode(s: &str, mode: Mode) -> Result<Document, TokenError> {
+        let x = Cursor::new(s).tokens_with_mode(mode)?;
+        JUST::new(&x, mode).parse_document::<()>()?;
+
+        let mut document = Document {
+            tape: Vec::with_capacity(x.len() / 2),
+            strings: String::with_capacity(s.len() / 2)
+        };
+        let mut tokens = x.iter();
+        if let Some((token, _)) = tokens.next() {
+            document.push_token(&mut tokens, token);
+        }
+
+        Ok(document)
+    }
+
+    /// The top level value
+    pub fn root(&self) -> Element<'_> {
+        Element {
+            document: self,
+            index: 0
+        }
+    }
+
+    /// Allows you get items with the same expressions as `JSON::get`, see `Element::get`
+    pub fn get<'a, S>(&self, expression: S) -> Option<JSON>
+    where
+        S: Into<&'a str>
+    {
+        self.root().get(expression)
+    }
+
+    /// Builds the whole document as a `JSON`
+    pub fn to_json(&self) -> JSON {
+        self.root().to_json()
+    }
+
+    fn push_token(&mut self, tokens: &mut slice::Iter<(Token, Span)>, token: &Token) {
+        match token {
+            Token::OpeningBracket => {
+                let at = self.tape.len();
+                self.tape.push(Node::Null);
+                let mut len = 0;
+                while let Some((token, _)) = tokens.next() {
+                    match token {
+                        Token::ClosingBracket => break,
+                        Token::Comma => continue,
+                        _ => {
+                            self.push_token(tokens, token);
+                            len += 1;
+                        }
+                    }
+                }
+                self.tape[at] = Node::Array { len, end: self.tape.len() };
+            },
+            Token::OpeningCurlyBrace => {
+                let at = self.tape.len();
+                self.tape.push(Node::Null);
+                let mut keys = Vec::new();
+                while let Some((token, _)) = tokens.next() {
+                    match token {
+                        Token::ClosingCurlyBrace => break,
+                        Token::Comma | Token::Colon => continue,
+                        Token::String(key) => {
+                            keys.push(self.tape.len());
+                            self.push_string(key);
+                            let (token, _) = tokens.by_ref().find(|(token, _)| !matches!(token, Token::Colon))
+                                .expect("validated objects always have a value after `:`");
+                            self.push_token(tokens, token);
+                        },
+                        _ => unreachable!("validated objects only have string keys, found {:?}", token)
+                    }
+                }
+                let len = self.shadow_repeated_keys(&keys);
+                self.tape[at] = Node::Object { len, end: self.tape.len() };
+            },
+            Token::String(string) => self.push_string(string),
+            Token::Boolean(boolean) => self.tape.push(Node::Boolean(*boolean)),
+            Token::Integer(int_val) => self.tape.push(Node::Integer(*int_val)),
+            Token::Unsigned(uint_val) => self.tape.push(Node::Unsigned(*uint_val)),
+            Token::Float(float) => self.tape.push(Node::Float(*float)),
+            _ => self.tape.push(Node::Null)
+        }
+    }
+
+    /// Marks every key at `keys` that the same key overrides later on, returning how many are left
+    fn shadow_repeated_keys(&mut self, keys: &[usize]) -> usize {
+        let mut last = Map::new();
+        for &index in keys {
+            last.insert(self.string(index).unwrap_or_default(), index);
+        }
+
+        let shadowed: Vec<usize> = keys.iter()
+            .copied()
+            .filter(|index| last.get(self.string(*index).unwrap_or_default()) != Some(index))
+            .collect();
+        let len = last.len();
+        for index in shadowed {
+            if let Node::String(start, end) = self.tape[index] {
+                self.tape[index] = Node::Shadowed(start, end);
+            }
+        }
+
+        len
+    }
+
+    fn push_string(&mut self, string: &str) {
+        let start = self.strings.len();
+        self.strings.push_str(string);
+        self.tape.push(Node::String(start, self.strings.len()));
+    }
+
+    fn push_json(&mut self, json: &JSON) {
+        match json {
+            JSON::Boolean(boolean) => self.tape.push(Node::Boolean(*boolean)),
+            JSON::Integer(int_val) => self.tape.push(Node::Integer(*int_val)),
+            JSON::Unsigned(uint_val) => self.tape.push(Node::Unsigned(*uint_val)),
+            JSON::Float(float) => self.tape.push(Node::Float(*float)),
+            JSON::Number(number) => {
+                let start = self.strings.len();
+                self.strings.push_str(number.as_decimal_str());
+                self.tape.push(Node::Number(start, self.strings.len()));
+            },
+            JSON::String(string) => self.push_string(string),
+            JSON::Array(json_arr) => {
+                let at = self.tape.len();
+                self.tape.push(Node::Null);
+                for item in json_arr {
+                    self.push_json(item);
+                }
+                self.tape[at] = Node::Array { len: json_arr.len(), end: self.tape.len() };
+            },
+            JSON::Object(json_obj) => {
+                let at = self.tape.len();
+                self.tape.push(Node::Null);
+                for (key, value) in json_obj {
+                    self.push_string(key);
+                    self.push_json(value);
+                }
+                self.tape[at] = Node::Object { len: json_obj.len(), end: self.tape.len() };
+            },
+            JSON::Null => self.tape.push(Node::Null),
+        }
+    }
+
+    /// The index just past the value at `index` and all of its children
+    fn end(&self, index: usize) -> usize {
+        match self.tape[index] {
+            Node::Array { end, .. } | Node::Object { end, .. } => end,
+            _ => index + 1
+        }
+    }
+
+    fn string(&self, index: usize) -> Option<&str> {
+        match self.tape[index] {
+            Node::String(start, end) => Some(&self.strings[start..end]),
+            _ => None
+        }
+    }
+}
+
+impl<'d> Element<'d> {
+    /// Returns the number of items in an array or members in an object
+    pub fn len(&self) -> Option<usize> {
+        match self.node() {
+            Node::Array { len, .. } | Node::Object { len, .. } => Some(len),
+            _ => None
+        }
+    }
+
+    /// Returns whether an array or object has nothing in it
+    pub fn is_empty(&self) -> Option<bool> {
+        self.len().map(|len| len == 0)
+    }
+
+    /// Returns the item at `index` of an array
+    pub fn at(&self, index: usize) -> Option<Element<'d>> {
+        self.members()?.nth(index)
+    }
+
+    /// Returns the value of `key` in an object
+    pub fn member(&self, key: &str) -> Option<Element<'d>> {
+        self.entries()?.find(|(name, _)| *name == key).map(|(_, value)| value)
+    }
+
+    /// Iterates over the items of an array
+    pub fn members(&self) -> Option<Members<'d>> {
+        match self.node() {
+            Node::Array { len, .. } => Some(Members {
+                document: self.document,
+                next: self.index + 1,
+                remaining: len
+            }),
+            _ => None
+        }
+    }
+
+    /// Iterates over the keys and values of an object
+    pub fn entries(&self) -> Option<Entries<'d>> {
+        match self.node() {
+            Node::Object { len, .. } => Some(Entries {
+                document: self.document,
+                next: self.index + 1,
+                remaining: len
+            }),
+            _ => None
+        }
+    }
+
+    /// Allows you get items with the same expressions as `JSON::get`, returning `None` when a key
+    /// or index on the path isn't there. Everything from the first `#` onwards runs as a normal
+    /// `JSON::get` on the value the path leads to.
+    pub fn get<'a, S>(&self, expression: S) -> Option<JSON>
+    where
+        S: Into<&'a str>
+    {
+        let expression = expression.into();
+        let (path, rest) = match expression.find('#') {
+            Some(index) => (expression[..index].trim_end_matches('.'), &expression[index..]),
+            None => (expression, "")
+        };
+
+        let mut element = *self;
+        for segment in path.split('.').filter(|segment| !segment.is_empty()) {
+            element = match element.node() {
+                Node::Array { .. } => element.at(segment.parse().ok()?)?,
+                _ => element.member(segment)?
+            };
+        }
+
+        match rest {
+            "" => Some(element.to_json()),
+            _ => Some(element.to_json().get(rest))
+        }
+    }
+
+    /// Builds this value as a `JSON`
+    pub fn to_json(&self) -> JSON {
+        match self.node() {
+            Node::Boolean(boolean) => JSON::Boolean(boolean),
+            Node::Integer(int_val) => JSON::Integer(int_val),
+            Node::Unsigned(uint_val) => JSON::Unsigned(uint_val),
+            Node::Float(float) => JSON::Float(float),
+            Node::Number(start, end) => JSON::Number(Number::from_literal(&self.document.strings[start..end])),
+            Node::String(start, end) => JSON::String(self.document.strings[start..end].to_string()),
+            Node::Array { .. } => JSON::Array(self.members().into_iter().flatten().map(|item| item.to_json()).collect()),
+            Node::Object { .. } => JSON::Object(self.entries().into_iter().flatten()
+                .map(|(key, value)| (key.to_string(), value.to_json()))
+                .collect::<Map<String, JSON>>()),
+            Node::Null => JSON::Null,
+            Node::Shadowed(..) => unreachable!("keys are never elements")
+        }
+    }
+
+    #[inline]
+    pub fn get_int(&self) -> Option<i64> {
+        match self.node() {
+            Node::Integer(int_val) => Some(int_val),
+            _ => None
+        }
+    }
+
+    /// Returns an Option Unsigned Integer 64, for any integer that isn't negative
+    #[inline]
+    pub fn get_unsigned(&self) -> Option<u64> {
+        match self.node() {
+            Node::Unsigned(uint_val) => Some(uint_val),
+            Node::Integer(int_val) if int_val >= 0 => Some(int_val as u64),
+            _ => None
+        }
+    }
+
+    #[inline]
+    pub fn get_float(&self) -> Option<f64> {
+        match self.node() {
+            Node::Float(float) => Some(float),
+            _ => None
+        }
+    }
+
+    #[inline]
+    pub fn get_number(&self) -> Option<Number> {
+        match self.node() {
+            Node::Number(start, end) => Some(Number::from_literal(&self.document.strings[start..end])),
+            _ => None
+        }
+    }
+
+    #[inline]
+    pub fn get_string(&self) -> Option<&'d str> {
+        self.document.string(self.index)
+    }
+
+    #[inline]
+    pub fn get_bool(&self) -> Option<bool> {
+        match self.node() {
+            Node::Boolean(boolean) => Some(boolean),
+            _ => None
+        }
+    }
+
+    #[inline]
+    pub fn is_null(&self) -> bool {
+        matches!(self.node(), Node::Null)
+    }
+
+    #[inline]
+    fn node(&self) -> Node {
+        self.document.tape[self.index]
+    }
+}
+
+impl<'d> Iterator for Members<'d> {
+    type Item = Element<'d>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None
+        }
+
+        let item = Element {
+            document: self.document,
+            index: self.next
+        };
+        self.next = self.document.end(self.next);
+        self.remaining -= 1;
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'d> Iterator for Entries<'d> {
+    type Item = (&'d str, Element<'d>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None
+        }
+
+        while let Node::Shadowed(..) = self.document.tape[self.next] {
+            self.next = self.document.end(self.next + 1);
+        }
+
+        let key = self.document.string(self.next).unwrap_or_default();
+        let value = Element {
+            document: self.document,
+            index: self.next + 1
+        };
+        self.next = self.document.end(self.next + 1);
+        self.remaining -= 1;
+        Some((key, value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl From<&JSON> for Document {
+    fn from(json: &JSON) -> Self {
+        let mut document = Document {
+            tape: Vec::new(),
+            strings: String::new()
+        };
+        document.push_json(json);
+        document
+    }
+}
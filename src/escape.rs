@@ -0,0 +1,47 @@
+//! # Escape
+//!
+//! How strings are escaped. `Display`, `JSONWriter` and the canonical form all write strings
+//! through the same escaper here, and `JSONWriter` lets its caller pick the `Escape` policy.
+
+use core::fmt::{self, Write};
+
+/// Which characters of strings and keys get written as escapes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Escape {
+    /// Only what JSON requires: `"`, `\` and control characters. The default
+    Minimal,
+
+    /// Everything outside ASCII as well, as `\uXXXX` with surrogate pairs past `U+FFFF`,
+    /// for systems that only take ASCII
+    ASCII,
+
+    /// `<`, `>`, `&`, `U+2028` and `U+2029` as well, so the output can go inside an HTML `<script>` tag
+    HTML,
+}
+
+/// Writes `string` in quotes. `\b`, `\f`, `\n`, `\r` and `\t` get their short escapes,
+/// and the other characters `escape` asks for are written as `\uXXXX` in lowercase hex.
+pub(crate) fn write<W: Write>(out: &mut W, string: &str, escape: Escape) -> fmt::Result {
+    out.write_char('"')?;
+    for character in string.chars() {
+        match (character, escape) {
+            ('"', _) => out.write_str("\\\"")?,
+            ('\\', _) => out.write_str("\\\\")?,
+            ('\u{8}', _) => out.write_str("\\b")?,
+            ('\u{c}', _) => out.write_str("\\f")?,
+            ('\n', _) => out.write_str("\\n")?,
+            ('\r', _) => out.write_str("\\r")?,
+            ('\t', _) => out.write_str("\\t")?,
+            ('\u{0}'..='\u{1f}', _)
+            | ('<' | '>' | '&' | '\u{2028}' | '\u{2029}', Escape::HTML)
+            | ('\u{80}'..=char::MAX, Escape::ASCII) => {
+                for unit in character.encode_utf16(&mut [0; 2]) {
+                    write!(out, "\\u{:04x}", unit)?;
+                }
+            },
+            _ => out.write_char(character)?
+        }
+    }
+
+    out.write_char('"')
+}
@@ -1,25 +1,43 @@
-use std::iter::Peekable;
-use std::str::Chars;
+use core::fmt;
+use core::iter::Peekable;
+use core::str::Chars;
 
+use crate::prelude::*;
 use crate::json::JSON;
 
 #[doc(hidden)]
-pub struct ParseExpression<'a> {
+pub struct ParseExpression<'a, T> {
     parse: Expressions<'a>,
-    json: &'a JSON,
+    json: &'a T,
 }
 
-impl<'a> ParseExpression<'a> {
-    pub fn new(expression: &'a str, json: &'a JSON) -> Self {
+/// The value types a `get` expression can walk
+pub(crate) trait Queryable: Clone + fmt::Debug {
+    /// The member `key`, or `None` if this isn't an object. Panics if the key is missing.
+    fn member(&self, key: &str) -> Option<Self>;
+
+    /// The item at `index`. Panics if this isn't an array or the index is out of bounds.
+    fn item(&self, index: usize) -> Self;
+
+    fn items(&self) -> Option<Vec<Self>>;
+    fn from_items(items: Vec<Self>) -> Self;
+    fn from_count(count: usize) -> Self;
+    fn int(&self) -> Option<i64>;
+    fn float(&self) -> Option<f64>;
+    fn string(&self) -> Option<&str>;
+}
+
+impl<'a, T: Queryable> ParseExpression<'a, T> {
+    pub fn new(expression: &'a str, json: &'a T) -> Self {
         Self {
             parse: Expressions::new(expression),
             json
         }
     }
 
-    pub fn parse_expression(&mut self) -> JSON {
+    pub fn parse_expression(&mut self) -> T {
         let mut hashtag = false;
-        let mut arr: Vec<JSON> = Vec::new();
+        let mut arr: Vec<T> = Vec::new();
         let mut json = self.json.to_owned();
         'expression_parse: while let Some(expression) = self.step() {
             match expression {
@@ -27,11 +45,11 @@ impl<'a> ParseExpression<'a> {
                 Expression::Key(ref key) => {
                     match hashtag {
                         true => {
-                            let mut return_arr: Vec<JSON> = Vec::new();
+                            let mut return_arr: Vec<T> = Vec::new();
                             for obj in arr.iter() {
-                                match obj.get_obj() {
-                                    Some(ref json_obj) => {
-                                        return_arr.push(json_obj[key].clone());
+                                match obj.member(key) {
+                                    Some(member) => {
+                                        return_arr.push(member);
                                     },
                                     None => panic!("Expected Object for `{}`!", key)
                                 }
@@ -47,11 +65,14 @@ impl<'a> ParseExpression<'a> {
                                         _ => panic!("Expected `.` after `{}`", key)
                                     }
                                 },
-                                None => return JSON::Array(return_arr)
+                                None => return T::from_items(return_arr)
                             }
                         },
                         false => {
-                            json = json[key.clone()].clone();
+                            json = match json.member(key) {
+                                Some(member) => member,
+                                None => panic!("Expected Object!")
+                            };
                             match self.parse.expression.peek() {
                                 Some(ref item) => {
                                     match item {
@@ -65,7 +86,7 @@ impl<'a> ParseExpression<'a> {
                     }
                 },
                 Expression::Index(ref index) => {
-                    json = json[index.clone()].clone();
+                    json = json.item(*index);
                     match self.parse.expression.peek() {
                         Some(ref item) => {
                             match item {
@@ -82,7 +103,7 @@ impl<'a> ParseExpression<'a> {
                             match item {
                                 '.' => {
                                     hashtag = true;
-                                    match json.get_arr() {
+                                    match json.items() {
                                         Some(json_arr) => arr = json_arr,
                                         None => panic!("Expected Array!")
                                     };
@@ -93,8 +114,8 @@ impl<'a> ParseExpression<'a> {
                             }
                         },
                         None => {
-                            match json.get_arr() {
-                                Some(arr) => return JSON::Integer(arr.len() as i64),
+                            match json.items() {
+                                Some(arr) => return T::from_count(arr.len()),
                                 None => panic!("Expected Array!")
                             }
                         }
@@ -102,9 +123,9 @@ impl<'a> ParseExpression<'a> {
                 },
                 Expression::Query(ref query) => {
                     let mut comparison: &str = "";
-                    let mut query_return_arr: Vec<JSON> = Vec::new();
+                    let mut query_return_arr: Vec<T> = Vec::new();
                     if *query.last().unwrap() == Expression::Hashtag {
-                        match json.get_arr() {
+                        match json.items() {
                             Some(json_arr) => {
                                 arr = json_arr.clone();
                                 query_return_arr = json_arr.clone();
@@ -118,15 +139,13 @@ impl<'a> ParseExpression<'a> {
                         match *expression {
                             Expression::Dot | Expression::Hashtag | Expression::OpenParantheses | Expression::ClosingParantheses => continue,
                             Expression::Key(ref key) => {
-                                eprintln!("KEY: {}", key);
                                 match hashtag {
                                     true => {
-                                        let mut return_arr: Vec<JSON> = Vec::new();
-                                        eprintln!("ARR: {:?}", arr);
+                                        let mut return_arr: Vec<T> = Vec::new();
                                         for obj in arr.iter() {
-                                            match obj.get_obj() {
-                                                Some(ref json_obj) => {
-                                                    return_arr.push(json_obj[key].clone());
+                                            match obj.member(key) {
+                                                Some(member) => {
+                                                    return_arr.push(member);
                                                 },
                                                 None => panic!("Expected Object for `{}`!", key)
                                             }
@@ -146,82 +165,61 @@ impl<'a> ParseExpression<'a> {
                                         }
                                     },
                                     false => {
-                                        json = json[key.clone()].clone();
+                                        json = match json.member(key) {
+                                            Some(member) => member,
+                                            None => panic!("Expected Object!")
+                                        };
                                     }
                                 }
                             },
                             Expression::Index(ref index) => {
-                                json = json[index.clone()].clone();
+                                json = json.item(*index);
                             },
                             Expression::GreaterThan => {
                                 comparison = ">";
                                 match query.get(i+1) {
-                                    Some(ref item) => {
-                                        match item {
-                                            Expression::Float(_) | Expression::Int(_) => continue,
-                                            _ => panic!("Expected `Int` or `Float` after `>`")
-                                        }
-                                    },
+                                    Some(Expression::Float(_) | Expression::Int(_)) => continue,
+                                    Some(_) => panic!("Expected `Int` or `Float` after `>`"),
                                     None => panic!("Expected `Int` or `Float` after `>`")
                                 }
                             },
                             Expression::GreaterThanEqual => {
                                 comparison = ">=";
                                 match query.get(i+1) {
-                                    Some(ref item) => {
-                                        match item {
-                                            Expression::Float(_) | Expression::Int(_) => continue,
-                                            _ => panic!("Expected `Int` or `Float` after `>=`")
-                                        }
-                                    },
+                                    Some(Expression::Float(_) | Expression::Int(_)) => continue,
+                                    Some(_) => panic!("Expected `Int` or `Float` after `>=`"),
                                     None => panic!("Expected `Int` or `Float` after `>=`")
                                 }
                             },
                             Expression::LessThan => {
                                 comparison = "<";
                                 match query.get(i+1) {
-                                    Some(ref item) => {
-                                        match item {
-                                            Expression::Float(_) | Expression::Int(_) => continue,
-                                            _ => panic!("Expected `Int` or `Float` after `<`")
-                                        }
-                                    },
+                                    Some(Expression::Float(_) | Expression::Int(_)) => continue,
+                                    Some(_) => panic!("Expected `Int` or `Float` after `<`"),
                                     None => panic!("Expected `Int` or `Float` after `<`")
                                 }
                             },
                             Expression::LessThanEqual => {
                                 comparison = "<=";
                                 match query.get(i+1) {
-                                    Some(ref item) => {
-                                        match item {
-                                            Expression::Float(_) | Expression::Int(_) => continue,
-                                            _ => panic!("Expected `Int` or `Float` after `<=`")
-                                        }
-                                    },
+                                    Some(Expression::Float(_) | Expression::Int(_)) => continue,
+                                    Some(_) => panic!("Expected `Int` or `Float` after `<=`"),
                                     None => panic!("Expected `Int` or `Float` after `<=`")
                                 }
                             },
                             Expression::EqualTo => {
                                 comparison = "==";
                                 match query.get(i+1) {
-                                    Some(ref item) => {
-                                        match item {
-                                            Expression::Float(_) | Expression::Int(_) | Expression::String(_) => continue,
-                                            _ => panic!("Expected `Int` or `Float` or `String` after `==`")
-                                        }
-                                    },
+                                    Some(Expression::Float(_) | Expression::Int(_) | Expression::String(_)) => continue,
+                                    Some(_) => panic!("Expected `Int` or `Float` or `String` after `==`"),
                                     None => panic!("Expected `Int` or `Float` or `String` after `==`")
                                 }
                             },
                             Expression::NotEqualTo => {
                                 comparison = "!=";
                                 match query.get(i+1) {
-                                    Some(ref item) => {
-                                        match item {
-                                            Expression::Float(_) | Expression::Int(_) | Expression::String(_) => continue,
-                                            _ => panic!("Expected `Int` or `Float` or `String` after `==`")
-                                        }
-                                    },
+                                    Some(Expression::Float(_) | Expression::Int(_) | Expression::String(_)) => continue,
+                                    Some(_) => panic!("Expected `Int` or `Float` or `String` after `==`"),
                                     None => panic!("Expected `Int` or `Float` or `String` after `==`")
                                 }
                             },
@@ -230,7 +228,7 @@ impl<'a> ParseExpression<'a> {
                                 match comparison {
                                     "==" => {
                                         for (i, item) in arr.iter().enumerate() {
-                                            match item.get_float() {
+                                            match item.float() {
                                                 Some(gotten_float) => {
                                                     if *float_val == gotten_float {
                                                         return_arr.push(query_return_arr[i].clone());
@@ -242,7 +240,7 @@ impl<'a> ParseExpression<'a> {
                                     },
                                     "!=" => {
                                         for (i, item) in arr.iter().enumerate() {
-                                            match item.get_float() {
+                                            match item.float() {
                                                 Some(gotten_float) => {
                                                     if *float_val != gotten_float {
                                                         return_arr.push(query_return_arr[i].clone());
@@ -254,7 +252,7 @@ impl<'a> ParseExpression<'a> {
                                     },
                                     "<=" => {
                                         for (i, item) in arr.iter().enumerate() {
-                                            match item.get_float() {
+                                            match item.float() {
                                                 Some(gotten_float) => {
                                                     if *float_val <= gotten_float {
                                                         return_arr.push(query_return_arr[i].clone());
@@ -266,7 +264,7 @@ impl<'a> ParseExpression<'a> {
                                     },
                                     "<" => {
                                         for (i, item) in arr.iter().enumerate() {
-                                            match item.get_float() {
+                                            match item.float() {
                                                 Some(gotten_float) => {
                                                     if *float_val < gotten_float {
                                                         return_arr.push(query_return_arr[i].clone());
@@ -278,7 +276,7 @@ impl<'a> ParseExpression<'a> {
                                     },
                                     ">" => {
                                         for (i, item) in arr.iter().enumerate() {
-                                            match item.get_float() {
+                                            match item.float() {
                                                 Some(gotten_float) => {
                                                     if *float_val > gotten_float {
                                                         return_arr.push(query_return_arr[i].clone());
@@ -290,7 +288,7 @@ impl<'a> ParseExpression<'a> {
                                     },
                                     ">=" => {
                                         for (i, item) in arr.iter().enumerate() {
-                                            match item.get_float() {
+                                            match item.float() {
                                                 Some(gotten_float) => {
                                                     if *float_val >= gotten_float {
                                                         return_arr.push(query_return_arr[i].clone());
@@ -310,7 +308,7 @@ impl<'a> ParseExpression<'a> {
                                 match comparison {
                                     "==" => {
                                         for (i, item) in arr.iter().enumerate() {
-                                            match item.get_int() {
+                                            match item.int() {
                                                 Some(gotten_int) => {
                                                     if *int_val == gotten_int {
                                                         return_arr.push(query_return_arr[i].clone());
@@ -322,7 +320,7 @@ impl<'a> ParseExpression<'a> {
                                     },
                                     "!=" => {
                                         for (i, item) in arr.iter().enumerate() {
-                                            match item.get_int() {
+                                            match item.int() {
                                                 Some(gotten_int) => {
                                                     if *int_val != gotten_int {
                                                         return_arr.push(query_return_arr[i].clone());
@@ -334,7 +332,7 @@ impl<'a> ParseExpression<'a> {
                                     },
                                     "<=" => {
                                         for (i, item) in arr.iter().enumerate() {
-                                            match item.get_int() {
+                                            match item.int() {
                                                 Some(gotten_int) => {
                                                     if *int_val <= gotten_int {
                                                         return_arr.push(query_return_arr[i].clone());
@@ -346,7 +344,7 @@ impl<'a> ParseExpression<'a> {
                                     },
                                     "<" => {
                                         for (i, item) in arr.iter().enumerate() {
-                                            match item.get_int() {
+                                            match item.int() {
                                                 Some(gotten_int) => {
                                                     if *int_val < gotten_int {
                                                         return_arr.push(query_return_arr[i].clone());
@@ -358,7 +356,7 @@ impl<'a> ParseExpression<'a> {
                                     },
                                     ">" => {
                                         for (i, item) in arr.iter().enumerate() {
-                                            match item.get_int() {
+                                            match item.int() {
                                                 Some(gotten_int) => {
                                                     if *int_val > gotten_int {
                                                         return_arr.push(query_return_arr[i].clone());
@@ -370,7 +368,7 @@ impl<'a> ParseExpression<'a> {
                                     },
                                     ">=" => {
                                         for (i, item) in arr.iter().enumerate() {
-                                            match item.get_int() {
+                                            match item.int() {
                                                 Some(gotten_int) => {
                                                     if *int_val >= gotten_int {
                                                         return_arr.push(query_return_arr[i].clone());
@@ -390,7 +388,7 @@ impl<'a> ParseExpression<'a> {
                                 match comparison {
                                     "==" => {
                                         for (i, item) in arr.iter().enumerate() {
-                                            match item.get_string() {
+                                            match item.string() {
                                                 Some(gotten_string) => {
                                                     if *string_val == gotten_string {
                                                         return_arr.push(query_return_arr[i].clone());
@@ -402,7 +400,7 @@ impl<'a> ParseExpression<'a> {
                                     },
                                     "!=" => {
                                         for (i, item) in arr.iter().enumerate() {
-                                            match item.get_string() {
+                                            match item.string() {
                                                 Some(gotten_string) => {
                                                     if *string_val != gotten_string {
                                                         return_arr.push(query_return_arr[i].clone());
@@ -430,12 +428,7 @@ impl<'a> ParseExpression<'a> {
 
     #[inline]
     fn step(&mut self) -> Option<Expression> {
-        let expression_type = self.parse.next();
-        if expression_type.is_some() {
-            Some(expression_type.unwrap())
-        } else {
-            None
-        }
+        self.parse.next()
     }
 }
 
@@ -513,7 +506,7 @@ impl<'a> Expressions<'a> {
         let mut slash =  false;
         let mut value = String::new();
 
-        'string: while let Some(character) = self.expression.next() {
+        'string: for character in self.expression.by_ref() {
             match character {
                 '\\' => {
                     value.push(character);
@@ -679,26 +672,63 @@ impl<'a> Iterator for Expressions<'a> {
     type Item = Expression;
 
     fn next(&mut self) -> Option<Self::Item> {
-        'expression: while let Some(character) = self.expression.next() {
-            return Some(match character {
-                'a'..='z' => self.get_key(character),
-                '0'..='9' => self.get_index(character),
-                '#' => {
-                    let parsed_hash = self.parse_hash();
-                    match parsed_hash {
-                        Some(express) => {
-                            express
-                        },
-                        None => {
-                            Expression::Hashtag
-                        }
+        let character = self.expression.next()?;
+        Some(match character {
+            'a'..='z' => self.get_key(character),
+            '0'..='9' => self.get_index(character),
+            '#' => {
+                let parsed_hash = self.parse_hash();
+                match parsed_hash {
+                    Some(express) => {
+                        express
+                    },
+                    None => {
+                        Expression::Hashtag
                     }
-                },
-                '.' => Expression::Dot,
-                _ => break 'expression
-            })
+                }
+            },
+            '.' => Expression::Dot,
+            _ => return None
+        })
+    }
+}
+
+impl Queryable for JSON {
+    fn member(&self, key: &str) -> Option<Self> {
+        match *self {
+            JSON::Object(ref json_obj) => Some(json_obj[key].clone()),
+            _ => None
         }
+    }
+
+    fn item(&self, index: usize) -> Self {
+        self[index].clone()
+    }
+
+    fn items(&self) -> Option<Vec<Self>> {
+        self.get_arr()
+    }
 
-        None
+    fn from_items(items: Vec<Self>) -> Self {
+        JSON::Array(items)
     }
-}
\ No newline at end of file
+
+    fn from_count(count: usize) -> Self {
+        JSON::Integer(count as i64)
+    }
+
+    fn int(&self) -> Option<i64> {
+        self.get_int()
+    }
+
+    fn float(&self) -> Option<f64> {
+        self.get_float()
+    }
+
+    fn string(&self) -> Option<&str> {
+        match *self {
+            JSON::String(ref string) => Some(string),
+            _ => None
+        }
+    }
+}
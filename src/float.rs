@@ -0,0 +1,252 @@
+//! # Float
+//!
+//! Finds the shortest decimal that reads back as a given `f64`, using the exact big integer method
+//! of Burger and Dybvig, so the digits are the same on every platform.
+
+use core::cmp::Ordering;
+
+use crate::prelude::*;
+
+/// An unsigned integer of any size, as 32 bit limbs with the least significant first
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Big {
+    limbs: Vec<u32>,
+}
+
+/// Writes `float`, which has to be finite, the way ECMAScript's `Number.prototype.toString` does:
+/// the shortest digits that parse back to it, laid out as a plain decimal from `1e-7` up to `1e21`
+/// and in exponent form outside that. With `keep_point` whole numbers get a `.0` and `-0.0` keeps
+/// its sign, so the value reads back as the same float and not an integer.
+pub(crate) fn write(out: &mut String, float: f64, keep_point: bool) {
+    debug_assert!(float.is_finite());
+    if float.is_sign_negative() && (float != 0.0 || keep_point) {
+        out.push('-');
+    }
+    if float == 0.0 {
+        out.push_str(if keep_point { "0.0" } else { "0" });
+        return
+    }
+
+    // The value is `0.digits * 10^point`
+    let (digits, point) = shortest(float.abs());
+    let length = digits.len() as i32;
+    let exponent = point - 1;
+    match point {
+        _ if length <= point && point <= 21 => {
+            out.push_str(&digits);
+            out.extend((length..point).map(|_| '0'));
+            if keep_point {
+                out.push_str(".0");
+            }
+        },
+        1..=21 => {
+            out.push_str(&digits[..point as usize]);
+            out.push('.');
+            out.push_str(&digits[point as usize..]);
+        },
+        -5..=0 => {
+            out.push_str("0.");
+            out.extend((point..0).map(|_| '0'));
+            out.push_str(&digits);
+        },
+        _ => {
+            out.push_str(&digits[..1]);
+            if length > 1 {
+                out.push('.');
+                out.push_str(&digits[1..]);
+            }
+            out.push_str(&format!("e{}{}", if exponent > 0 { "+" } else { "-" }, exponent.abs()));
+        }
+    }
+}
+
+/// Returns the shortest digits `d1 d2 .. dn` and the `point` where `0.d1d2..dn * 10^point` reads back
+/// as `float`. When two candidates are equally short and equally close, the last digit is the even one,
+/// the same as ECMAScript. `float` has to be finite and above zero.
+fn shortest(float: f64) -> (String, i32) {
+    debug_assert!(float.is_finite() && float > 0.0);
+    let bits = float.to_bits();
+    let biased = ((bits >> 52) & 0x7ff) as i32;
+    let fraction = bits & ((1 << 52) - 1);
+    let (mantissa, exponent) = match biased {
+        0 => (fraction, -1074),
+        _ => (fraction | (1 << 52), biased - 1075)
+    };
+
+    // The value is `r / s`. Everything less than `m_minus / s` below it or `m_plus / s` above it
+    // reads back as the same float, and when the mantissa is even so do the two boundaries.
+    // Right above a power of two the floats below are twice as dense, so the gap below is half as big.
+    let inclusive = mantissa % 2 == 0;
+    let power_of_two = fraction == 0 && biased > 1;
+    let (mut r, mut s, mut m_plus, mut m_minus) = match (exponent >= 0, power_of_two) {
+        (true, false) => (Big::pow2(mantissa, exponent as u32 + 1), Big::new(2), Big::pow2(1, exponent as u32), Big::pow2(1, exponent as u32)),
+        (true, true) => (Big::pow2(mantissa, exponent as u32 + 2), Big::new(4), Big::pow2(1, exponent as u32 + 1), Big::pow2(1, exponent as u32)),
+        (false, false) => (Big::new(mantissa * 2), Big::pow2(1, (1 - exponent) as u32), Big::new(1), Big::new(1)),
+        (false, true) => (Big::new(mantissa * 4), Big::pow2(1, (2 - exponent) as u32), Big::new(2), Big::new(1))
+    };
+
+    // Guess low from the binary exponent, `78913 / 2^18` is just under `log10(2)`, then move up
+    // until the upper boundary fits under `10^point`
+    let bit_length = 64 - mantissa.leading_zeros() as i64;
+    let mut point = (((exponent as i64 + bit_length - 1) * 78913) >> 18) as i32 - 1;
+    match point {
+        0.. => s.mul_pow10(point as u32),
+        _ => {
+            r.mul_pow10(-point as u32);
+            m_plus.mul_pow10(-point as u32);
+            m_minus.mul_pow10(-point as u32);
+        }
+    }
+    while past(&r.add(&m_plus), &s, inclusive) {
+        s.mul_small(10);
+        point += 1;
+    }
+
+    let mut digits = String::new();
+    loop {
+        r.mul_small(10);
+        m_plus.mul_small(10);
+        m_minus.mul_small(10);
+
+        let mut digit = 0;
+        while r >= s {
+            r.sub_assign(&s);
+            digit += 1;
+        }
+
+        let low = match inclusive {
+            true => r <= m_minus,
+            false => r < m_minus
+        };
+        let high = past(&r.add(&m_plus), &s, inclusive);
+        match (low, high) {
+            (false, false) => {
+                digits.push(char::from(b'0' + digit));
+                continue
+            },
+            (true, false) => {},
+            (false, true) => digit += 1,
+            (true, true) => {
+                let mut twice = r.clone();
+                twice.mul_small(2);
+                match twice.cmp(&s) {
+                    Ordering::Less => {},
+                    Ordering::Greater => digit += 1,
+                    Ordering::Equal => digit += digit % 2
+                }
+            }
+        }
+
+        digits.push(char::from(b'0' + digit));
+        return (digits, point)
+    }
+}
+
+/// Whether `high` reaches `limit`, counting a tie when the boundaries are inclusive
+fn past(high: &Big, limit: &Big, inclusive: bool) -> bool {
+    match inclusive {
+        true => high >= limit,
+        false => high > limit
+    }
+}
+
+impl Big {
+    fn new(value: u64) -> Big {
+        let mut big = Big {
+            limbs: vec![value as u32, (value >> 32) as u32]
+        };
+        big.trim();
+        big
+    }
+
+    /// `value * 2^bits`
+    fn pow2(value: u64, bits: u32) -> Big {
+        let mut big = Big::new(value);
+        let mut limbs = vec![0; (bits / 32) as usize];
+        let shift = bits % 32;
+        let mut carry = 0;
+        for limb in big.limbs {
+            limbs.push(match shift {
+                0 => limb,
+                _ => (limb << shift) | carry
+            });
+            carry = match shift {
+                0 => 0,
+                _ => limb >> (32 - shift)
+            };
+        }
+        limbs.push(carry);
+
+        big.limbs = limbs;
+        big.trim();
+        big
+    }
+
+    fn trim(&mut self) {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+    }
+
+    fn mul_small(&mut self, factor: u32) {
+        let mut carry = 0;
+        for limb in self.limbs.iter_mut() {
+            let product = *limb as u64 * factor as u64 + carry;
+            *limb = product as u32;
+            carry = product >> 32;
+        }
+        if carry > 0 {
+            self.limbs.push(carry as u32);
+        }
+    }
+
+    fn mul_pow10(&mut self, mut exponent: u32) {
+        while exponent >= 9 {
+            self.mul_small(1_000_000_000);
+            exponent -= 9;
+        }
+        self.mul_small(10u32.pow(exponent));
+    }
+
+    fn add(&self, other: &Big) -> Big {
+        let mut limbs = Vec::with_capacity(self.limbs.len().max(other.limbs.len()) + 1);
+        let mut carry = 0;
+        for i in 0..self.limbs.len().max(other.limbs.len()) {
+            let sum = *self.limbs.get(i).unwrap_or(&0) as u64 + *other.limbs.get(i).unwrap_or(&0) as u64 + carry;
+            limbs.push(sum as u32);
+            carry = sum >> 32;
+        }
+        limbs.push(carry as u32);
+
+        let mut big = Big {
+            limbs
+        };
+        big.trim();
+        big
+    }
+
+    /// Subtracts `other`, which can't be bigger than `self`
+    fn sub_assign(&mut self, other: &Big) {
+        let mut borrow = 0;
+        for (i, limb) in self.limbs.iter_mut().enumerate() {
+            let (difference, under) = limb.overflowing_sub(*other.limbs.get(i).unwrap_or(&0));
+            let (difference, under_borrow) = difference.overflowing_sub(borrow);
+            *limb = difference;
+            borrow = (under || under_borrow) as u32;
+        }
+        self.trim();
+    }
+}
+
+impl Ord for Big {
+    fn cmp(&self, other: &Big) -> Ordering {
+        self.limbs.len().cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for Big {
+    fn partial_cmp(&self, other: &Big) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
@@ -2,17 +2,38 @@
 //!
 //! This is all the functions JSON struct can do.
 
-use std::{
+use alloc::borrow::Cow;
+use core::{
+    convert::TryFrom,
+    fmt,
     ops,
-    collections::HashMap,
     str::FromStr
 };
 
+use crate::prelude::*;
+use crate::escape::{self, Escape};
 use crate::expression::ParseExpression;
-use crate::just::{TokenError, JUST, Tokenize};
+use crate::float;
+use crate::just::{Build, Cursor, Mode, Span, TokenError, JUST, Tokenize};
+use crate::number::Number;
+use crate::spans::{Diagnostic, LineIndex, Spans};
+
+/// The map objects are stored in, a `HashMap` with the `std` feature and a `BTreeMap` without it
+#[cfg(feature = "std")]
+pub type Map<K, V> = std::collections::HashMap<K, V>;
+
+/// The map objects are stored in, a `HashMap` with the `std` feature and a `BTreeMap` without it
+#[cfg(not(feature = "std"))]
+pub type Map<K, V> = alloc::collections::BTreeMap<K, V>;
+
+#[cfg(feature = "std")]
+use std::collections::hash_map as map;
+
+#[cfg(not(feature = "std"))]
+use alloc::collections::btree_map as map;
 
 /// Represents all the JSON values
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
 pub enum JSON {
     /// JSON Boolean
     Boolean(bool),
@@ -20,9 +41,15 @@ pub enum JSON {
     /// JSON Integer
     Integer(i64),
 
+    /// JSON Integer above `i64::MAX`, smaller ones are always `JSON::Integer`
+    Unsigned(u64),
+
     /// JSON Float
     Float(f64),
 
+    /// JSON Number kept exactly as written, see `JSON::parse_exact_numbers`
+    Number(Number),
+
     /// JSON String
     String(String),
 
@@ -30,18 +57,28 @@ pub enum JSON {
     Array(Vec<JSON>),
 
     /// JSON Object
-    Object(HashMap<String, JSON>),
+    Object(Map<String, JSON>),
 
     /// JSON Null
     Null,
 }
 
+/// The error from changing a `JSON` as a kind of value it isn't, like pushing to an object
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypeError {
+    ExpectedObject,
+    ExpectedArray,
+    ExpectedContainer,
+}
+
 impl JSON {
      /// Allows you get items from a JSON
     ///
     /// # Examples
     ///
     /// ```
+    /// # use json_parser::{array, object};
+    /// # use json_parser::json::JSON;
     /// let json: JSON = object!{
     ///     "kind" => "youtube#searchListResponse",
     ///     "etag" => "m2yskBQFythfE4irbTIeOgYYfBU/PaiEDiVxOyCWelLPuuwa9LKz3Gk",
@@ -84,22 +121,8 @@ impl JSON {
     /// assert_eq!(json.get("items.#"), JSON::Integer(3));
     /// assert_eq!(json.get("items.#(id.kind=='youtube#video')#.etag"),
     /// array![
-    ///     object!{
-    ///         "kind" => "youtube#searchResult",
-    ///         "etag" => "m2yskBQFythfE4irbTIeOgYYfBU/AWutzVOt_5p1iLVifyBdfoSTf9E",
-    ///         "id" => object!{
-    ///            "kind" => "youtube#video",
-    ///            "videoId" => "Eqa2nAAhHN0"
-    ///         }
-    ///     },
-    ///     object!{
-    ///         "kind" => "youtube#searchResult",
-    ///         "etag" => "m2yskBQFythfE4irbTIeOgYYfBU/2dIR9BTfr7QphpBuY3hPU-h5u-4",
-    ///         "id" => object!{
-    ///             "kind" => "youtube#video",
-    ///             "videoId" => "IirngItQuVs"
-    ///         }
-    ///     }
+    ///     "m2yskBQFythfE4irbTIeOgYYfBU/AWutzVOt_5p1iLVifyBdfoSTf9E",
+    ///     "m2yskBQFythfE4irbTIeOgYYfBU/2dIR9BTfr7QphpBuY3hPU-h5u-4"
     /// ]);
     /// assert_eq!(json.get("items.2"),
     /// object!{
@@ -124,8 +147,10 @@ impl JSON {
     /// # Examples
     ///
     /// ```
+    /// # use json_parser::{array, object};
+    /// # use json_parser::json::JSON;
     /// let array = array![5, 10, true, false, array![1, 2, 3]];
-    /// assert_eq!(array[4].get_arr(), Some(array![1, 2, 3]));
+    /// assert_eq!(array[4].get_arr(), array![1, 2, 3].get_arr());
     ///
     /// let obj = object!{
     ///     "how are you" => "Good",
@@ -133,7 +158,7 @@ impl JSON {
     ///         1, 3, true, "hello"
     ///     ]
     /// };
-    /// assert_eq!(object["words"].get_arr(), Some(array![1, 3, true, "hello"]));
+    /// assert_eq!(obj["words"].get_arr(), array![1, 3, true, "hello"].get_arr());
     /// ```
     #[inline]
     pub fn get_arr(&self) -> Option<Vec<JSON>> {
@@ -148,19 +173,22 @@ impl JSON {
     /// # Examples
     ///
     /// ```
+    /// # use json_parser::{array, object};
+    /// # use json_parser::json::JSON;
     /// let array = array!["hello", "bye", object!{"you" => "cute"}];
-    /// assert_eq!(array[2], Some(object!{"you" => "cute"}));
+    /// assert_eq!(array[2].get_obj(), object!{"you" => "cute"}.get_obj());
     ///
     /// let obj = object!{
     ///     "cringe" => "no you",
-    ///     "cities" => object {
+    ///     "cities" => object!{
     ///         "california" => "San diego",
     ///         "texas" => "Dallas"
     ///     }
     /// };
-    /// assert_eq!(obj["cities"].get_obj(), Some(object!{"california" => "San diego", "texas" => "Dallas"}));
+    /// assert_eq!(obj["cities"].get_obj(), object!{"california" => "San diego", "texas" => "Dallas"}.get_obj());
+    /// ```
     #[inline]
-    pub fn get_obj(&self) -> Option<HashMap<String, JSON>> {
+    pub fn get_obj(&self) -> Option<Map<String, JSON>> {
         match self {
             JSON::Object(json_obj) => Some(json_obj.to_owned()),
             _ => None
@@ -172,7 +200,9 @@ impl JSON {
     /// # Example
     ///
     /// ```
-    /// let array = array![1, 2.5, true, 10, false, array![1, 2, 3], 5.6, "hello", obj{"cringe" => "you"}, "no u", JSON::Null];
+    /// # use json_parser::{array, object};
+    /// # use json_parser::json::JSON;
+    /// let array = array![1, 2.5, true, 10, false, array![1, 2, 3], 5.6, "hello", object!{"cringe" => "you"}, "no u", JSON::Null];
     /// assert_eq!(array[0].get_int(), Some(1));
     /// assert_eq!(array[3].get_int(), Some(10));
     /// ```
@@ -184,12 +214,35 @@ impl JSON {
         }
     }
 
+    /// Returns an Option Unsigned Integer 64, for any integer that isn't negative
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::str::FromStr;
+    /// # use json_parser::json::JSON;
+    /// let array = JSON::from_str("[18446744073709551615, 10, 1.5]").unwrap();
+    /// assert_eq!(array[0].get_unsigned(), Some(u64::MAX));
+    /// assert_eq!(array[1].get_unsigned(), Some(10));
+    /// assert_eq!(array[2].get_unsigned(), None);
+    /// ```
+    #[inline]
+    pub fn get_unsigned(&self) -> Option<u64> {
+        match *self {
+            JSON::Unsigned(uint_val) => Some(uint_val),
+            JSON::Integer(int_val) if int_val >= 0 => Some(int_val as u64),
+            _ => None
+        }
+    }
+
     /// Returns an Option Float 64
     ///
     /// # Example
     ///
     /// ```
-    /// let array = array![1, 2.5, true, 10, false, array![1, 2, 3], 5.6, "hello", obj{"cringe" => "you"}, "no u", JSON::Null];
+    /// # use json_parser::{array, object};
+    /// # use json_parser::json::JSON;
+    /// let array = array![1, 2.5, true, 10, false, array![1, 2, 3], 5.6, "hello", object!{"cringe" => "you"}, "no u", JSON::Null];
     /// assert_eq!(array[1].get_float(), Some(2.5));
     /// assert_eq!(array[6].get_float(), Some(5.6));
     /// ```
@@ -201,12 +254,33 @@ impl JSON {
         }
     }
 
+    /// Returns an Option Number, for documents parsed with `JSON::parse_exact_numbers`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use json_parser::json::JSON;
+    /// # use json_parser::just::Mode;
+    /// let json = JSON::parse_exact_numbers("[18446744073709551616, 0.1]", Mode::Strict).unwrap();
+    /// assert_eq!(json[0].get_number().unwrap().as_i128(), Some(18446744073709551616));
+    /// assert_eq!(json[1].get_number().unwrap().as_decimal_str(), "0.1");
+    /// ```
+    #[inline]
+    pub fn get_number(&self) -> Option<Number> {
+        match *self {
+            JSON::Number(ref number) => Some(number.to_owned()),
+            _ => None
+        }
+    }
+
     /// Returns an Option String
     ///
     /// # Example
     ///
     /// ```
-    /// let array = array![1, 2.5, true, 10, false, array![1, 2, 3], 5.6, "hello", obj{"cringe" => "you"}, "no u", JSON::Null];
+    /// # use json_parser::{array, object};
+    /// # use json_parser::json::JSON;
+    /// let array = array![1, 2.5, true, 10, false, array![1, 2, 3], 5.6, "hello", object!{"cringe" => "you"}, "no u", JSON::Null];
     /// assert_eq!(array[7].get_string(), Some(String::from("hello")));
     /// assert_eq!(array[9].get_string(), Some(String::from("no u")));
     /// ```
@@ -223,7 +297,9 @@ impl JSON {
     /// # Example
     ///
     /// ```
-    /// let array = array![1, 2.5, true, 10, false, array![1, 2, 3], 5.6, "hello", obj{"cringe" => "you"}, "no u", JSON::Null];
+    /// # use json_parser::{array, object};
+    /// # use json_parser::json::JSON;
+    /// let array = array![1, 2.5, true, 10, false, array![1, 2, 3], 5.6, "hello", object!{"cringe" => "you"}, "no u", JSON::Null];
     /// assert_eq!(array[2].get_bool(), Some(true));
     /// assert_eq!(array[4].get_bool(), Some(false));
     /// ```
@@ -235,34 +311,704 @@ impl JSON {
         }
     }
 
+    /// Parses JSONC, JSON that allows `//` and `/* */` comments and trailing commas
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use json_parser::{array, object};
+    /// # use json_parser::json::JSON;
+    /// let json = JSON::from_jsonc(r#"{
+    ///     // The port to listen on
+    ///     "port": 8080,
+    ///     "hosts": ["localhost", /* "0.0.0.0", */],
+    /// }"#).unwrap();
+    /// assert_eq!(json["port"], 8080);
+    /// assert_eq!(json["hosts"], array!["localhost"]);
+    /// ```
+    pub fn from_jsonc(s: &str) -> Result<JSON, TokenError> {
+        JSON::parse_with_mode(s, Mode::JSONC)
+    }
+
+    /// Parses the JSON and also records the line and column every value came from
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use json_parser::json::JSON;
+    /// # use json_parser::just::Mode;
+    /// let (json, spans) = JSON::parse_with_spans(r#"{
+    ///     "items": [
+    ///         { "price": 10 },
+    ///         { "price": 0 }
+    ///     ]
+    /// }"#, Mode::Strict).unwrap();
+    ///
+    /// assert_eq!(json.get("items.1.price"), 0);
+    /// assert_eq!(format!("items[1].price at {}", spans.get("items.1.price").unwrap()), "items[1].price at line 4, col 20");
+    /// ```
+    pub fn parse_with_spans(s: &str, mode: Mode) -> Result<(JSON, Spans), TokenError> {
+        let x = Cursor::new(s).tokens_with_mode(mode)?;
+        let mut just = JUST::new(&x, mode).record_spans();
+        let json = just.parse()?;
+        let spans = just.take_spans().unwrap_or_default();
+
+        Ok((json, Spans::new(s, spans)))
+    }
+
+    /// Parses as much as it can, reporting every syntax error instead of stopping at the first.
+    /// After an error the parser skips to the next `,`, `}` or `]`, so the returned value holds
+    /// everything that could be read around the mistakes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use json_parser::{array, json::JSON};
+    /// # use json_parser::just::Mode;
+    /// let (json, diagnostics) = JSON::parse_recovering("[1, 2 3, ?, 4]", Mode::Strict);
+    /// assert_eq!(json, array![1, 2, 4]);
+    /// assert_eq!(diagnostics.len(), 3);
+    /// assert_eq!(diagnostics[0].location.to_string(), "line 1, col 7");
+    /// ```
+    pub fn parse_recovering(s: &str, mode: Mode) -> (JSON, Vec<Diagnostic>) {
+        let (x, mut errors) = Cursor::new(s).tokens_recovering(mode);
+        let mut just = JUST::new(&x, mode).recover_errors();
+        let json = match just.parse() {
+            Ok(json) => json,
+            Err(err) => {
+                errors.push((err, Span { start: 0, end: s.len() }));
+                JSON::Null
+            }
+        };
+        errors.extend(just.take_diagnostics());
+        errors.sort_by_key(|(_, span)| span.start);
+
+        let index = LineIndex::new(s);
+        let diagnostics = errors.into_iter()
+            .map(|(error, span)| Diagnostic { error, location: index.location(span) })
+            .collect();
+
+        (json, diagnostics)
+    }
+
+    /// Parses the JSON keeping every number as `JSON::Number`, the exact text it was written as,
+    /// so big integers and decimals survive a round trip
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use json_parser::json::JSON;
+    /// # use json_parser::just::Mode;
+    /// let source = r#"{"id":123456789012345678901234,"price":0.10}"#;
+    /// let json = JSON::parse_exact_numbers(source, Mode::Strict).unwrap();
+    /// assert_eq!(json["id"].get_number().unwrap().as_u64(), None);
+    /// assert_eq!(json["price"].to_string(), "0.10");
+    /// ```
+    pub fn parse_exact_numbers(s: &str, mode: Mode) -> Result<JSON, TokenError> {
+        let x = Cursor::new(s).tokens_with_mode(mode)?;
+        let mut just = JUST::new(&x, mode).exact_numbers(s);
+        just.parse()
+    }
+
+    /// Reads everything from `reader` and parses it as JSON
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// # use json_parser::json::JSON;
+    /// let json = JSON::from_reader(File::open("src/test1.json").unwrap()).unwrap();
+    /// assert_eq!(json.get("pageInfo.totalResults"), 4249);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn from_reader<R: std::io::Read>(mut reader: R) -> Result<JSON, TokenError> {
+        let mut contents = String::new();
+        if let Err(err) = reader.read_to_string(&mut contents) {
+            return Err(TokenError::Msg(format!("Could not read JSON -> {}", err)))
+        }
+
+        contents.parse()
+    }
+
+    fn parse_with_mode(s: &str, mode: Mode) -> Result<JSON, TokenError> {
+        let x = Cursor::new(s).tokens_with_mode(mode)?;
+        let mut just = JUST::new(&x, mode);
+        just.parse()
+    }
+
     /// Returns an Boolean, and checks if it's a JSON::Null type
     ///
     /// # Example
     ///
     /// ```
-    /// let array = array![1, 2.5, true, 10, false, array![1, 2, 3], 5.6, "hello", obj{"cringe" => "you"}, "no u", JSON::Null];
-    /// assert_eq!(array[8].is_null(), Some(false));
-    /// assert_eq!(array[10].is_null(), Some(true));
+    /// # use json_parser::{array, object};
+    /// # use json_parser::json::JSON;
+    /// let array = array![1, 2.5, true, 10, false, array![1, 2, 3], 5.6, "hello", object!{"cringe" => "you"}, "no u", JSON::Null];
+    /// assert_eq!(array[8].is_null(), false);
+    /// assert_eq!(array[10].is_null(), true);
     /// ```
     #[inline]
     pub fn is_null(&self) -> bool {
+        matches!(*self, JSON::Null)
+    }
+
+    /// Sets `key` of an object to `value`, returning the value it replaced.
+    /// Fails if the JSON isn't an object.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use json_parser::{array, object};
+    /// # use json_parser::json::TypeError;
+    /// let mut obj = object!{"name" => "old"};
+    /// assert_eq!(obj.insert("name", "new"), Ok(Some("old".into())));
+    /// assert_eq!(obj.insert("id", 3), Ok(None));
+    /// assert_eq!(obj, object!{"name" => "new", "id" => 3});
+    /// assert_eq!(array![1].insert("id", 3), Err(TypeError::ExpectedObject));
+    /// ```
+    pub fn insert<K, V>(&mut self, key: K, value: V) -> Result<Option<JSON>, TypeError>
+    where
+        K: Into<String>,
+        V: Into<JSON>
+    {
+        match *self {
+            JSON::Object(ref mut json_obj) => Ok(json_obj.insert(key.into(), value.into())),
+            _ => Err(TypeError::ExpectedObject)
+        }
+    }
+
+    /// Removes `key` from an object and returns its value, or `None` if the key isn't there.
+    /// Fails if the JSON isn't an object.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use json_parser::object;
+    /// let mut obj = object!{"keep" => 1, "drop" => 2};
+    /// assert_eq!(obj.remove("drop"), Ok(Some(2.into())));
+    /// assert_eq!(obj.remove("drop"), Ok(None));
+    /// assert_eq!(obj, object!{"keep" => 1});
+    /// ```
+    pub fn remove(&mut self, key: &str) -> Result<Option<JSON>, TypeError> {
+        match *self {
+            JSON::Object(ref mut json_obj) => Ok(json_obj.remove(key)),
+            _ => Err(TypeError::ExpectedObject)
+        }
+    }
+
+    /// Adds `value` to the end of an array. Fails if the JSON isn't an array.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use json_parser::{array, object};
+    /// # use json_parser::json::TypeError;
+    /// let mut arr = array![1];
+    /// arr.push("two").unwrap();
+    /// assert_eq!(arr, array![1, "two"]);
+    /// assert_eq!(object!{"a" => 1}.push(3), Err(TypeError::ExpectedArray));
+    /// ```
+    pub fn push<V: Into<JSON>>(&mut self, value: V) -> Result<(), TypeError> {
+        match *self {
+            JSON::Array(ref mut json_arr) => {
+                json_arr.push(value.into());
+                Ok(())
+            },
+            _ => Err(TypeError::ExpectedArray)
+        }
+    }
+
+    /// Removes the last item of an array and returns it, or `None` if it's empty.
+    /// Fails if the JSON isn't an array.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use json_parser::array;
+    /// let mut arr = array![1, 2];
+    /// assert_eq!(arr.pop(), Ok(Some(2.into())));
+    /// assert_eq!(arr.pop(), Ok(Some(1.into())));
+    /// assert_eq!(arr.pop(), Ok(None));
+    /// ```
+    pub fn pop(&mut self) -> Result<Option<JSON>, TypeError> {
+        match *self {
+            JSON::Array(ref mut json_arr) => Ok(json_arr.pop()),
+            _ => Err(TypeError::ExpectedArray)
+        }
+    }
+
+    /// Moves the value out, leaving `JSON::Null` in its place
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use json_parser::{array, object};
+    /// # use json_parser::json::JSON;
+    /// let mut obj = object!{"items" => array![1, 2]};
+    /// let items = obj.get_mut("items").unwrap().unwrap().take();
+    /// assert_eq!(items, array![1, 2]);
+    /// assert_eq!(obj, object!{"items" => JSON::Null});
+    /// ```
+    pub fn take(&mut self) -> JSON {
+        core::mem::take(self)
+    }
+
+    /// Keeps only the array items, or the object values, that `keep` returns `true` for.
+    /// Fails if the JSON isn't an array or an object.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use json_parser::{array, object};
+    /// # use json_parser::json::{JSON, TypeError};
+    /// let mut arr = array![1, "two", 3];
+    /// arr.retain(|item| item.get_int().is_some()).unwrap();
+    /// assert_eq!(arr, array![1, 3]);
+    ///
+    /// let mut obj = object!{"a" => 1, "b" => "two"};
+    /// obj.retain(|value| value.get_string().is_some()).unwrap();
+    /// assert_eq!(obj, object!{"b" => "two"});
+    /// assert_eq!(JSON::Null.retain(|_| true), Err(TypeError::ExpectedContainer));
+    /// ```
+    pub fn retain<F>(&mut self, mut keep: F) -> Result<(), TypeError>
+    where
+        F: FnMut(&JSON) -> bool
+    {
+        match *self {
+            JSON::Array(ref mut json_arr) => json_arr.retain(|item| keep(item)),
+            JSON::Object(ref mut json_obj) => json_obj.retain(|_, value| keep(value)),
+            _ => return Err(TypeError::ExpectedContainer)
+        }
+
+        Ok(())
+    }
+
+    /// Returns the value at `key` of an object to change, or `None` if the key isn't there.
+    /// Fails if the JSON isn't an object.
+    pub fn get_mut(&mut self, key: &str) -> Result<Option<&mut JSON>, TypeError> {
+        match *self {
+            JSON::Object(ref mut json_obj) => Ok(json_obj.get_mut(key)),
+            _ => Err(TypeError::ExpectedObject)
+        }
+    }
+
+    /// Returns `key` of an object to read or fill in place. Fails if the JSON isn't an object.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use json_parser::{array, object};
+    /// # use json_parser::json::JSON;
+    /// let mut obj = JSON::from(object!{});
+    /// for tag in ["a", "b", "a"] {
+    ///     obj.entry(tag).unwrap().or_insert_with(|| array![].into()).push(1).unwrap();
+    /// }
+    /// *obj.entry("b").unwrap().or_insert(0) = JSON::Null;
+    /// assert_eq!(obj, object!{"a" => array![1, 1], "b" => JSON::Null});
+    /// assert!(JSON::from(7).entry("a").is_err());
+    /// ```
+    pub fn entry<K: Into<String>>(&mut self, key: K) -> Result<Entry<'_>, TypeError> {
         match *self {
-            JSON::Null => true,
-            _ => false,
+            JSON::Object(ref mut json_obj) => Ok(Entry {
+                json_obj,
+                key: key.into()
+            }),
+            _ => Err(TypeError::ExpectedObject)
         }
     }
+
+    /// Iterates over the items of an array, anything else has none
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use json_parser::array;
+    /// let arr = array![1, 2, 3];
+    /// assert_eq!(arr.members().filter_map(|item| item.get_int()).sum::<i64>(), 6);
+    /// assert_eq!(arr[0].members().count(), 0);
+    /// ```
+    pub fn members(&self) -> core::slice::Iter<'_, JSON> {
+        match *self {
+            JSON::Array(ref json_arr) => json_arr.iter(),
+            _ => [].iter()
+        }
+    }
+
+    /// Iterates over the items of an array to change them, anything else has none
+    pub fn members_mut(&mut self) -> core::slice::IterMut<'_, JSON> {
+        match *self {
+            JSON::Array(ref mut json_arr) => json_arr.iter_mut(),
+            _ => [].iter_mut()
+        }
+    }
+
+    /// Iterates over the keys and values of an object, in the map's order. Anything else has none.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use json_parser::object;
+    /// let obj = object!{"a" => 1};
+    /// assert_eq!(obj.entries().collect::<Vec<_>>(), vec![("a", &1.into())]);
+    /// ```
+    pub fn entries(&self) -> Entries<'_> {
+        Entries {
+            iter: match *self {
+                JSON::Object(ref json_obj) => Some(json_obj.iter()),
+                _ => None
+            }
+        }
+    }
+
+    /// Iterates over the keys and values of an object to change the values. Anything else has none.
+    pub fn entries_mut(&mut self) -> EntriesMut<'_> {
+        EntriesMut {
+            iter: match *self {
+                JSON::Object(ref mut json_obj) => Some(json_obj.iter_mut()),
+                _ => None
+            }
+        }
+    }
+
+    /// Returns the number of items in an array, members in an object or bytes in a string.
+    /// Only those have a length, anything else returns 0.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use json_parser::{array, object};
+    /// # use json_parser::json::JSON;
+    /// assert_eq!(array![1, 2].len(), 2);
+    /// assert_eq!(object!{"a" => 1}.len(), 1);
+    /// assert_eq!(JSON::from("héllo").len(), 6);
+    /// assert_eq!(JSON::from(7).len(), 0);
+    /// ```
+    pub fn len(&self) -> usize {
+        match *self {
+            JSON::Array(ref json_arr) => json_arr.len(),
+            JSON::Object(ref json_obj) => json_obj.len(),
+            JSON::String(ref string) => string.len(),
+            _ => 0
+        }
+    }
+
+    /// Whether an array, object or string is empty. Like `len`, it's only meaningful for those,
+    /// anything else counts as empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use json_parser::{array, object};
+    /// # use json_parser::json::JSON;
+    /// assert!(JSON::from(array![]).is_empty());
+    /// assert!(!JSON::from("a").is_empty());
+    /// assert!(JSON::Null.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// The keys and values of an object, from `JSON::entries`
+#[derive(Debug)]
+pub struct Entries<'a> {
+    iter: Option<map::Iter<'a, String, JSON>>,
+}
+
+/// The keys and values of an object, from `JSON::entries_mut`
+#[derive(Debug)]
+pub struct EntriesMut<'a> {
+    iter: Option<map::IterMut<'a, String, JSON>>,
+}
+
+impl<'a> Iterator for Entries<'a> {
+    type Item = (&'a str, &'a JSON);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, value) = self.iter.as_mut()?.next()?;
+        Some((key.as_str(), value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.as_ref().map_or((0, Some(0)), |iter| iter.size_hint())
+    }
+}
+
+impl<'a> Iterator for EntriesMut<'a> {
+    type Item = (&'a str, &'a mut JSON);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, value) = self.iter.as_mut()?.next()?;
+        Some((key.as_str(), value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.as_ref().map_or((0, Some(0)), |iter| iter.size_hint())
+    }
+}
+
+/// The items of an array or the values of an object, from iterating over a `&JSON`
+#[derive(Debug)]
+pub struct Iter<'a> {
+    items: core::slice::Iter<'a, JSON>,
+    values: Option<map::Values<'a, String, JSON>>,
+}
+
+/// The items of an array or the values of an object, from iterating over a `&mut JSON`
+#[derive(Debug)]
+pub struct IterMut<'a> {
+    items: core::slice::IterMut<'a, JSON>,
+    values: Option<map::ValuesMut<'a, String, JSON>>,
+}
+
+/// The items of an array or the values of an object, from iterating over an owned `JSON`
+#[derive(Debug)]
+pub struct IntoIter {
+    items: alloc::vec::IntoIter<JSON>,
+    values: Option<map::IntoValues<String, JSON>>,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = &'a JSON;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.items.next().or_else(|| self.values.as_mut()?.next())
+    }
+}
+
+impl<'a> Iterator for IterMut<'a> {
+    type Item = &'a mut JSON;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.items.next().or_else(|| self.values.as_mut()?.next())
+    }
+}
+
+impl Iterator for IntoIter {
+    type Item = JSON;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.items.next().or_else(|| self.values.as_mut()?.next())
+    }
+}
+
+/// Iterates over the items of an owned array or the values of an owned object, in the map's order.
+/// Anything else has none.
+///
+/// # Example
+///
+/// ```
+/// # use json_parser::{array, object};
+/// let names: Vec<String> = array!["a", "b"].into_iter().filter_map(|item| item.get_string()).collect();
+/// assert_eq!(names, ["a", "b"]);
+/// assert_eq!(object!{"a" => 1}.into_iter().collect::<Vec<_>>(), [1]);
+/// ```
+impl IntoIterator for JSON {
+    type Item = JSON;
+    type IntoIter = IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        match self {
+            JSON::Array(json_arr) => IntoIter {
+                items: json_arr.into_iter(),
+                values: None
+            },
+            JSON::Object(json_obj) => IntoIter {
+                items: Vec::new().into_iter(),
+                values: Some(json_obj.into_values())
+            },
+            _ => IntoIter {
+                items: Vec::new().into_iter(),
+                values: None
+            }
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a JSON {
+    type Item = &'a JSON;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Iter {
+            items: self.members(),
+            values: match *self {
+                JSON::Object(ref json_obj) => Some(json_obj.values()),
+                _ => None
+            }
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a mut JSON {
+    type Item = &'a mut JSON;
+    type IntoIter = IterMut<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        match *self {
+            JSON::Object(ref mut json_obj) => IterMut {
+                items: [].iter_mut(),
+                values: Some(json_obj.values_mut())
+            },
+            _ => IterMut {
+                items: self.members_mut(),
+                values: None
+            }
+        }
+    }
+}
+
+/// A key of an object that may or may not be there yet, from `JSON::entry`
+#[derive(Debug)]
+pub struct Entry<'a> {
+    json_obj: &'a mut Map<String, JSON>,
+    key: String,
+}
+
+impl<'a> Entry<'a> {
+    /// Returns the key
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Returns the value, first inserting `default` if the key isn't there
+    pub fn or_insert<V: Into<JSON>>(self, default: V) -> &'a mut JSON {
+        self.json_obj.entry(self.key).or_insert_with(|| default.into())
+    }
+
+    /// Returns the value, first inserting what `default` returns if the key isn't there
+    pub fn or_insert_with<F: FnOnce() -> JSON>(self, default: F) -> &'a mut JSON {
+        self.json_obj.entry(self.key).or_insert_with(default)
+    }
+
+    /// Calls `modify` on the value if the key is there
+    pub fn and_modify<F: FnOnce(&mut JSON)>(self, modify: F) -> Self {
+        if let Some(value) = self.json_obj.get_mut(&self.key) {
+            modify(value);
+        }
+
+        self
+    }
+}
+
+/// `JSON::Null`
+impl Default for JSON {
+    fn default() -> Self {
+        JSON::Null
+    }
 }
 
 impl FromStr for JSON {
     type Err = TokenError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let x = s.chars().peekable().tokens()?;
-        let mut just = JUST {
-            tokens: Box::new(x.iter())
-        };
+        JSON::parse_with_mode(s, Mode::Strict)
+    }
+}
+
+impl fmt::Display for TypeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            TypeError::ExpectedObject => write!(f, "Expected Object!"),
+            TypeError::ExpectedArray => write!(f, "Expected Array!"),
+            TypeError::ExpectedContainer => write!(f, "Expected Array or Object!")
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TypeError {}
+
+/// Writes the JSON compactly, with no whitespace between tokens. Floats are written with the
+/// shortest digits that read back as the same `f64`, and `NaN` and infinities as `null`.
+///
+/// # Example
+///
+/// ```
+/// # use json_parser::array;
+/// assert_eq!(array![0.1, 2.0, 1e21, 5e-324, f64::NAN].to_string(), "[0.1,2.0,1e+21,5e-324,null]");
+/// ```
+impl fmt::Display for JSON {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            JSON::Boolean(boolean) => write!(f, "{}", boolean),
+            JSON::Integer(int_val) => write!(f, "{}", int_val),
+            JSON::Unsigned(uint_val) => write!(f, "{}", uint_val),
+            JSON::Float(float) if !float.is_finite() => write!(f, "null"),
+            JSON::Float(float) => {
+                let mut decimal = String::new();
+                float::write(&mut decimal, float, true);
+                write!(f, "{}", decimal)
+            },
+            JSON::Number(ref number) => write!(f, "{}", number),
+            JSON::String(ref string) => escape::write(f, string, Escape::Minimal),
+            JSON::Array(ref json_arr) => {
+                write!(f, "[")?;
+                for (i, item) in json_arr.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            },
+            JSON::Object(ref json_obj) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in json_obj.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    escape::write(f, key, Escape::Minimal)?;
+                    write!(f, ":{}", value)?;
+                }
+                write!(f, "}}")
+            },
+            JSON::Null => write!(f, "null"),
+        }
+    }
+}
+
+impl<'a> Build<'a> for JSON {
+    type Key = String;
+
+    fn key(key: Cow<'a, str>) -> String {
+        key.into_owned()
+    }
+
+    fn string(string: Cow<'a, str>) -> Self {
+        JSON::String(string.into_owned())
+    }
+
+    fn boolean(bool_val: bool) -> Self {
+        JSON::Boolean(bool_val)
+    }
+
+    fn integer(int_val: i64) -> Self {
+        JSON::Integer(int_val)
+    }
+
+    fn unsigned(uint_val: u64) -> Self {
+        JSON::Unsigned(uint_val)
+    }
 
-        Ok(just.parse()?)
+    fn float(float_val: f64) -> Self {
+        JSON::Float(float_val)
+    }
+
+    fn number(number: Number) -> Self {
+        JSON::Number(number)
+    }
+
+    fn array(arr: Vec<JSON>) -> Self {
+        JSON::Array(arr)
+    }
+
+    fn object(object: Map<String, JSON>) -> Self {
+        JSON::Object(object)
+    }
+
+    fn null() -> Self {
+        JSON::Null
     }
 }
 
@@ -284,14 +1030,13 @@ impl ops::Index<usize> for JSON {
     }
 }
 
-impl<'a> ops::Index<&'a str> for JSON {
+impl ops::Index<&str> for JSON {
     type Output = JSON;
 
     fn index(&self, index: &str) -> &Self::Output {
         match *self {
             JSON::Object(ref json_obj) => {
-                println!("{}", index);
-                match json_obj.get(&format!("{}", index)) {
+                match json_obj.get(&index.to_string()) {
                     Some(obj_item) => {
                         obj_item
                     },
@@ -309,8 +1054,7 @@ impl ops::Index<String> for JSON {
     fn index(&self, index: String) -> &Self::Output {
         match *self {
             JSON::Object(ref json_obj) => {
-                println!("{:?}", json_obj);
-                match json_obj.get(&format!("{}", index)) {
+                match json_obj.get(&index.to_string()) {
                     Some(obj_item) => {
                         obj_item
                     },
@@ -328,12 +1072,34 @@ impl From<i64> for JSON {
     }
 }
 
+/// Lets untyped integer literals like the ones in `array![1, 2, 3]` pick an impl
+impl From<i32> for JSON {
+    fn from(item: i32) -> Self {
+        JSON::Integer(item as i64)
+    }
+}
+
+impl From<u64> for JSON {
+    fn from(item: u64) -> Self {
+        match i64::try_from(item) {
+            Ok(int_val) => JSON::Integer(int_val),
+            Err(_) => JSON::Unsigned(item)
+        }
+    }
+}
+
 impl From<f64> for JSON {
     fn from(item: f64) -> Self {
         JSON::Float(item)
     }
 }
 
+impl From<Number> for JSON {
+    fn from(item: Number) -> Self {
+        JSON::Number(item)
+    }
+}
+
 impl From<bool> for JSON {
     fn from(item: bool) -> Self {
         JSON::Boolean(item)
@@ -350,43 +1116,105 @@ impl From<Vec<JSON>> for JSON {
     fn from(items: Vec<JSON>) -> Self {
         let mut arr = Vec::new();
         for item in items {
-            arr.push(item.into());
+            arr.push(item);
         }
 
         JSON::Array(arr)
     }
 }
 
-impl From<HashMap<String, JSON>> for JSON {
-    fn from(map: HashMap<String, JSON>) -> Self {
-        let mut hm = HashMap::new();
+impl From<Map<String, JSON>> for JSON {
+    fn from(map: Map<String, JSON>) -> Self {
+        let mut hm = Map::new();
         for (key, val) in map {
-            hm.insert(key, val.into());
+            hm.insert(key, val);
         }
 
         JSON::Object(hm)
     }
 }
 
-impl<'a> From<&'a str> for JSON {
+impl From<&str> for JSON {
     fn from(item: &str) -> Self {
         JSON::String(item.to_string())
     }
 }
 
-impl PartialEq<i64> for JSON {
-    fn eq(&self, other: &i64) -> bool {
-        match *self {
-            JSON::Integer(ref value) => value == other,
+/// Compares any JSON number with an integer by value
+fn int_eq(json: &JSON, other: i128) -> bool {
+    match *json {
+        JSON::Integer(value) => value as i128 == other,
+        JSON::Unsigned(value) => value as i128 == other,
+        JSON::Float(value) => float_eq_int(value, other),
+        JSON::Number(ref number) => number.as_i128() == Some(other),
+        _ => false
+    }
+}
+
+fn float_eq_int(float: f64, int: i128) -> bool {
+    float as i128 == int && int as f64 == float
+}
+
+/// Compares like the values they hold, so numbers are equal by value whether they're stored
+/// as `Integer`, `Unsigned`, `Float` or `Number`
+///
+/// # Example
+///
+/// ```
+/// # use json_parser::json::JSON;
+/// assert_eq!(JSON::Integer(2), JSON::Float(2.0));
+/// assert_eq!(JSON::Unsigned(1 << 63), JSON::Float(9223372036854775808.0));
+/// assert_ne!(JSON::Unsigned(u64::MAX), JSON::Float(u64::MAX as f64));
+/// assert_ne!(JSON::Integer(-1), JSON::Unsigned(u64::MAX));
+/// assert_ne!(JSON::Integer(1), JSON::from(true));
+/// ```
+impl PartialEq for JSON {
+    fn eq(&self, other: &JSON) -> bool {
+        match (self, other) {
+            (JSON::Boolean(a), JSON::Boolean(b)) => a == b,
+            (JSON::String(a), JSON::String(b)) => a == b,
+            (JSON::Array(a), JSON::Array(b)) => a == b,
+            (JSON::Object(a), JSON::Object(b)) => a == b,
+            (JSON::Null, JSON::Null) => true,
+            (JSON::Number(a), JSON::Number(b)) if a == b => true,
+            (_, JSON::Integer(int_val)) => int_eq(self, *int_val as i128),
+            (_, JSON::Unsigned(uint_val)) => int_eq(self, *uint_val as i128),
+            (_, JSON::Float(float)) => self == float,
+            (_, JSON::Number(number)) => match (number.as_i128(), number.as_f64()) {
+                (Some(int_val), _) => int_eq(self, int_val),
+                (_, Some(float)) => self == &float,
+                _ => false
+            },
             _ => false
         }
     }
 }
 
+impl PartialEq<i64> for JSON {
+    fn eq(&self, other: &i64) -> bool {
+        int_eq(self, *other as i128)
+    }
+}
+
+impl PartialEq<i32> for JSON {
+    fn eq(&self, other: &i32) -> bool {
+        int_eq(self, *other as i128)
+    }
+}
+
+impl PartialEq<u64> for JSON {
+    fn eq(&self, other: &u64) -> bool {
+        int_eq(self, *other as i128)
+    }
+}
+
 impl PartialEq<f64> for JSON {
     fn eq(&self, other: &f64) -> bool {
         match *self {
             JSON::Float(ref value) => value == other,
+            JSON::Integer(value) => float_eq_int(*other, value as i128),
+            JSON::Unsigned(value) => float_eq_int(*other, value as i128),
+            JSON::Number(ref number) => number.as_f64() == Some(*other),
             _ => false
         }
     }
@@ -428,8 +1256,8 @@ impl PartialEq<Vec<JSON>> for JSON {
     }
 }
 
-impl PartialEq<HashMap<String, JSON>> for JSON {
-    fn eq(&self, other: &HashMap<String, JSON>) -> bool {
+impl PartialEq<Map<String, JSON>> for JSON {
+    fn eq(&self, other: &Map<String, JSON>) -> bool {
         match *self {
             JSON::Object(ref value) => value == other,
             _ => false
@@ -0,0 +1,293 @@
+//! # JSONRef
+//!
+//! A JSON value that borrows its strings from the source text instead of copying them.
+//! Only strings with escapes in them have to be decoded into a copy.
+
+use alloc::borrow::Cow;
+use core::ops;
+
+use crate::prelude::*;
+use crate::expression::{ParseExpression, Queryable};
+use crate::json::{Map, JSON};
+use crate::just::{Build, Cursor, Mode, TokenError, JUST, Tokenize};
+use crate::number::Number;
+
+/// Represents all the JSON values, with strings and keys sliced out of the source.
+/// Use it for large documents you only read from, and `into_owned` for the parts you keep.
+///
+/// # Example
+///
+/// ```
+/// # use std::borrow::Cow;
+/// # use json_parser::json_ref::JSONRef;
+/// let source = r#"{"pageInfo": {"totalResults": 4249}, "items": [{"kind": "youtube#video"}]}"#;
+/// let json = JSONRef::parse(source).unwrap();
+/// assert_eq!(json.get("pageInfo.totalResults").get_int(), Some(4249));
+/// assert_eq!(json["items"][0]["kind"].get_string(), Some("youtube#video"));
+/// assert!(matches!(json["items"][0]["kind"], JSONRef::String(Cow::Borrowed(_))));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum JSONRef<'a> {
+    /// JSON Boolean
+    Boolean(bool),
+
+    /// JSON Integer
+    Integer(i64),
+
+    /// JSON Integer above `i64::MAX`, smaller ones are always `JSONRef::Integer`
+    Unsigned(u64),
+
+    /// JSON Float
+    Float(f64),
+
+    /// JSON Number kept exactly as written
+    Number(Number),
+
+    /// JSON String, borrowed from the source unless it had escapes to decode
+    String(Cow<'a, str>),
+
+    /// JSON Array
+    Array(Vec<JSONRef<'a>>),
+
+    /// JSON Object, with keys borrowed from the source unless they had escapes to decode
+    Object(Map<Cow<'a, str>, JSONRef<'a>>),
+
+    /// JSON Null
+    Null,
+}
+
+impl<'a> JSONRef<'a> {
+    /// Parses strict JSON without copying its strings
+    pub fn parse(s: &'a str) -> Result<JSONRef<'a>, TokenError> {
+        JSONRef::parse_with_mode(s, Mode::Strict)
+    }
+
+    /// Parses JSONC, JSON that allows `//` and `/* */` comments and trailing commas
+    pub fn from_jsonc(s: &'a str) -> Result<JSONRef<'a>, TokenError> {
+        JSONRef::parse_with_mode(s, Mode::JSONC)
+    }
+
+    fn parse_with_mode(s: &'a str, mode: Mode) -> Result<JSONRef<'a>, TokenError> {
+        let x = Cursor::new(s).tokens_with_mode(mode)?;
+        let mut just = JUST::new(&x, mode);
+        just.parse_borrowed()
+    }
+
+    /// Allows you get items from a JSONRef, with the same expressions as `JSON::get`
+    pub fn get<'b, S>(&self, expression: S) -> JSONRef<'a>
+    where
+        S: Into<&'b str>
+    {
+        let expression = expression.into();
+        ParseExpression::new(expression, self).parse_expression()
+    }
+
+    /// Copies the value into a `JSON` that no longer borrows from the source
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::str::FromStr;
+    /// # use json_parser::json::JSON;
+    /// # use json_parser::json_ref::JSONRef;
+    /// let source = r#"[1, 2.5, "hello", {"you": null}]"#;
+    /// assert_eq!(JSONRef::parse(source).unwrap().into_owned(), JSON::from_str(source).unwrap());
+    /// ```
+    pub fn into_owned(self) -> JSON {
+        match self {
+            JSONRef::Boolean(boolean) => JSON::Boolean(boolean),
+            JSONRef::Integer(int_val) => JSON::Integer(int_val),
+            JSONRef::Unsigned(uint_val) => JSON::Unsigned(uint_val),
+            JSONRef::Float(float) => JSON::Float(float),
+            JSONRef::Number(number) => JSON::Number(number),
+            JSONRef::String(string) => JSON::String(string.into_owned()),
+            JSONRef::Array(json_arr) => JSON::Array(json_arr.into_iter().map(JSONRef::into_owned).collect()),
+            JSONRef::Object(json_obj) => JSON::Object(json_obj.into_iter()
+                .map(|(key, value)| (key.into_owned(), value.into_owned()))
+                .collect()),
+            JSONRef::Null => JSON::Null,
+        }
+    }
+
+    /// Returns an Option Array (slice)
+    #[inline]
+    pub fn get_arr(&self) -> Option<&[JSONRef<'a>]> {
+        match self {
+            JSONRef::Array(json_arr) => Some(json_arr),
+            _ => None
+        }
+    }
+
+    /// Returns an Option Object (Hashmap)
+    #[inline]
+    pub fn get_obj(&self) -> Option<&Map<Cow<'a, str>, JSONRef<'a>>> {
+        match self {
+            JSONRef::Object(json_obj) => Some(json_obj),
+            _ => None
+        }
+    }
+
+    /// Returns an Option Integer 64
+    #[inline]
+    pub fn get_int(&self) -> Option<i64> {
+        match *self {
+            JSONRef::Integer(int_val) => Some(int_val),
+            _ => None
+        }
+    }
+
+    /// Returns an Option Unsigned Integer 64, for any integer that isn't negative
+    #[inline]
+    pub fn get_unsigned(&self) -> Option<u64> {
+        match *self {
+            JSONRef::Unsigned(uint_val) => Some(uint_val),
+            JSONRef::Integer(int_val) if int_val >= 0 => Some(int_val as u64),
+            _ => None
+        }
+    }
+
+    /// Returns an Option Float 64
+    #[inline]
+    pub fn get_float(&self) -> Option<f64> {
+        match *self {
+            JSONRef::Float(float) => Some(float),
+            _ => None
+        }
+    }
+
+    /// Returns an Option Number
+    #[inline]
+    pub fn get_number(&self) -> Option<&Number> {
+        match *self {
+            JSONRef::Number(ref number) => Some(number),
+            _ => None
+        }
+    }
+
+    /// Returns an Option String, borrowed from the source where possible
+    #[inline]
+    pub fn get_string(&self) -> Option<&str> {
+        match *self {
+            JSONRef::String(ref string) => Some(string),
+            _ => None
+        }
+    }
+
+    /// Returns an Option Boolean
+    #[inline]
+    pub fn get_bool(&self) -> Option<bool> {
+        match *self {
+            JSONRef::Boolean(boolean) => Some(boolean),
+            _ => None
+        }
+    }
+
+    /// Returns an Boolean, and checks if it's a JSONRef::Null type
+    #[inline]
+    pub fn is_null(&self) -> bool {
+        matches!(*self, JSONRef::Null)
+    }
+}
+
+impl<'a> Build<'a> for JSONRef<'a> {
+    type Key = Cow<'a, str>;
+
+    fn key(key: Cow<'a, str>) -> Cow<'a, str> {
+        key
+    }
+
+    fn string(string: Cow<'a, str>) -> Self {
+        JSONRef::String(string)
+    }
+
+    fn boolean(bool_val: bool) -> Self {
+        JSONRef::Boolean(bool_val)
+    }
+
+    fn integer(int_val: i64) -> Self {
+        JSONRef::Integer(int_val)
+    }
+
+    fn unsigned(uint_val: u64) -> Self {
+        JSONRef::Unsigned(uint_val)
+    }
+
+    fn float(float_val: f64) -> Self {
+        JSONRef::Float(float_val)
+    }
+
+    fn number(number: Number) -> Self {
+        JSONRef::Number(number)
+    }
+
+    fn array(arr: Vec<JSONRef<'a>>) -> Self {
+        JSONRef::Array(arr)
+    }
+
+    fn object(object: Map<Cow<'a, str>, JSONRef<'a>>) -> Self {
+        JSONRef::Object(object)
+    }
+
+    fn null() -> Self {
+        JSONRef::Null
+    }
+}
+
+impl<'a> Queryable for JSONRef<'a> {
+    fn member(&self, key: &str) -> Option<Self> {
+        match *self {
+            JSONRef::Object(ref json_obj) => Some(json_obj[key].clone()),
+            _ => None
+        }
+    }
+
+    fn item(&self, index: usize) -> Self {
+        self[index].clone()
+    }
+
+    fn items(&self) -> Option<Vec<Self>> {
+        self.get_arr().map(|json_arr| json_arr.to_vec())
+    }
+
+    fn from_items(items: Vec<Self>) -> Self {
+        JSONRef::Array(items)
+    }
+
+    fn from_count(count: usize) -> Self {
+        JSONRef::Integer(count as i64)
+    }
+
+    fn int(&self) -> Option<i64> {
+        self.get_int()
+    }
+
+    fn float(&self) -> Option<f64> {
+        self.get_float()
+    }
+
+    fn string(&self) -> Option<&str> {
+        self.get_string()
+    }
+}
+
+impl<'a> ops::Index<usize> for JSONRef<'a> {
+    type Output = JSONRef<'a>;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        match self.get_arr().and_then(|json_arr| json_arr.get(index)) {
+            Some(arr_item) => arr_item,
+            None => panic!("Expected Array!")
+        }
+    }
+}
+
+impl<'a> ops::Index<&str> for JSONRef<'a> {
+    type Output = JSONRef<'a>;
+
+    fn index(&self, index: &str) -> &Self::Output {
+        match self.get_obj().and_then(|json_obj| json_obj.get(index)) {
+            Some(obj_item) => obj_item,
+            None => panic!("Expected Object!")
+        }
+    }
+}
@@ -2,31 +2,104 @@
 //!
 //! A struct Parser which is used to take in the incoming str full of json data and convert it to a JSON type
 
-use std::collections::HashMap;
-use std::iter::Peekable;
-use std::str::Chars;
+use alloc::borrow::Cow;
+use core::fmt;
+use core::hash::Hash;
+use core::iter::Peekable;
+use core::slice;
+use core::str::{CharIndices, Chars};
 
-use crate::json::JSON;
+use crate::prelude::*;
+use crate::json::{Map, JSON};
+use crate::json_ref::JSONRef;
+use crate::number::Number;
+use crate::scan::Scanner;
 
-pub struct JUST<'a> {
-    pub tokens: Box<dyn Iterator<Item = &'a Token> + 'a>,
+/// How many arrays and objects can be nested inside each other before the parser gives up,
+/// so hostile input can't overflow the stack
+const MAX_DEPTH: usize = 128;
+
+pub struct JUST<'t, 'a> {
+    tokens: Peekable<slice::Iter<'t, (Token<'a>, Span)>>,
+    pub mode: Mode,
+    spans: Option<Map<String, Span>>,
+    diagnostics: Option<SpannedErrors>,
+    open_arrays: usize,
+    open_objects: usize,
+    exact_numbers: Option<&'a str>,
+    path: Vec<String>,
+    end: usize,
+}
+
+/// Which dialect of JSON the tokenizer and parser accept
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Mode {
+    /// Plain JSON, nothing extra allowed
+    Strict,
+
+    /// JSON with `//` and `/* */` comments and trailing commas, like VS Code's `.jsonc` files
+    JSONC,
 }
 
 #[derive(Debug, Clone)]
-pub enum Token {
+pub enum Token<'a> {
     OpeningCurlyBrace,
     ClosingCurlyBrace,
     OpeningBracket,
     ClosingBracket,
     Colon,
     Comma,
-    String(String),
+    /// The text between the quotes with its escapes decoded, borrowed from the source when it has none
+    String(Cow<'a, str>),
     Boolean(bool),
     Integer(i64),
+    Unsigned(u64),
     Float(f64),
     Null
 }
 
+/// The values `JUST` can build, so the same grammar produces both `JSON` and `JSONRef`
+pub(crate) trait Build<'a>: Sized {
+    type Key: Eq + Hash + Ord;
+
+    fn key(key: Cow<'a, str>) -> Self::Key;
+    fn string(string: Cow<'a, str>) -> Self;
+    fn boolean(bool_val: bool) -> Self;
+    fn integer(int_val: i64) -> Self;
+    fn unsigned(uint_val: u64) -> Self;
+    fn float(float_val: f64) -> Self;
+    fn number(number: Number) -> Self;
+    fn array(arr: Vec<Self>) -> Self;
+    fn object(object: Map<Self::Key, Self>) -> Self;
+    fn null() -> Self;
+}
+
+/// A byte range `start..end` into the source text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Errors found while tokenizing or parsing, each with where it happened
+pub type SpannedErrors = Vec<(TokenError, Span)>;
+
+/// A token or a run of trivia (whitespace and comments) with where it sits in the source
+#[derive(Debug, Clone)]
+pub enum Lexeme<'a> {
+    Token(Token<'a>, Span),
+    Whitespace(Span),
+    Comment(Span),
+}
+
+/// A `char` iterator over the source that knows its byte offset
+pub struct Cursor<'a> {
+    source: &'a str,
+    base: usize,
+    chars: Peekable<CharIndices<'a>>,
+    scanner: Scanner,
+}
+
 #[derive(Debug)]
 pub enum TokenError {
     FloatTokenError(String),
@@ -36,240 +109,522 @@ pub enum TokenError {
     Msg(String)
 }
 
+impl fmt::Display for TokenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            TokenError::FloatTokenError(ref msg) => write!(f, "Invalid float: {}", msg),
+            TokenError::IntTokenError(ref msg) => write!(f, "Invalid integer: {}", msg),
+            TokenError::ValueTokenError(ref value) => write!(f, "Unknown value `{}`", value),
+            TokenError::UnknownChar(ref character) => write!(f, "Unknown character `{}`", character),
+            TokenError::Msg(ref msg) => write!(f, "{}", msg)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TokenError {}
+
 pub trait Tokenize<'a>: Sized {
     type TokenVec;
 
-    fn tokens(&mut self) -> Result<Self::TokenVec, TokenError>;
+    fn tokens(&mut self) -> Result<Self::TokenVec, TokenError> {
+        self.tokens_with_mode(Mode::Strict)
+    }
 
-    fn tokenize_string(&mut self) -> Result<Token, TokenError>;
-    fn tokenize_number(&mut self, first_char: char) -> Result<Token, TokenError>;
-    fn tokenize_value(&mut self, first_char: char) -> Result<Token, TokenError>;
+    fn tokens_with_mode(&mut self, mode: Mode) -> Result<Self::TokenVec, TokenError>;
+    fn lexemes(&mut self, mode: Mode) -> Result<Vec<Lexeme<'a>>, TokenError>;
+
+    fn skip_comment(&mut self) -> Result<(), TokenError>;
+    fn tokenize_string(&mut self) -> Result<Token<'a>, TokenError>;
+    fn tokenize_number(&mut self, first_char: char) -> Result<Token<'a>, TokenError>;
+    fn tokenize_value(&mut self, first_char: char) -> Result<Token<'a>, TokenError>;
 }
 
-impl<'a> JUST<'a> {
+impl<'t, 'a> JUST<'t, 'a> {
+    pub fn new(tokens: &'t [(Token<'a>, Span)], mode: Mode) -> Self {
+        Self {
+            tokens: tokens.iter().peekable(),
+            mode,
+            spans: None,
+            diagnostics: None,
+            open_arrays: 0,
+            open_objects: 0,
+            exact_numbers: None,
+            path: Vec::new(),
+            end: 0
+        }
+    }
+
+    /// Makes the parser record the span of every value it builds, keyed by its dot path
+    pub fn record_spans(mut self) -> Self {
+        self.spans = Some(Map::new());
+        self
+    }
+
+    /// Makes the parser record syntax errors instead of stopping at the first one.
+    /// After an error it skips ahead to the next `,`, `}` or `]` and carries on.
+    pub fn recover_errors(mut self) -> Self {
+        self.diagnostics = Some(Vec::new());
+        self
+    }
+
+    /// Makes the parser build `JSON::Number` from the literal text in `source`
+    /// instead of converting numbers to `i64` or `f64`
+    pub fn exact_numbers(mut self, source: &'a str) -> Self {
+        self.exact_numbers = Some(source);
+        self
+    }
+
     pub fn parse(&mut self) -> Result<JSON, TokenError> {
-        match self.tokens.next() {
-            Some(token) => {
-                match token {
-                    Token::OpeningCurlyBrace => Ok(JSON::Object(self.parse_object()?)),
-                    Token::OpeningBracket => Ok(JSON::Array(self.parse_array()?)),
-                    _ => Err(TokenError::Msg("Unexpected Start of File.\nShould be `{{` or `[`".to_string()))
-                }
+        self.parse_document()
+    }
+
+    /// Parses into a `JSONRef` whose strings borrow from the source the tokens came from
+    pub fn parse_borrowed(&mut self) -> Result<JSONRef<'a>, TokenError> {
+        self.parse_document()
+    }
+
+    pub(crate) fn parse_document<V: Build<'a>>(&mut self) -> Result<V, TokenError> {
+        let json = match self.next_token() {
+            Some((token @ (Token::ClosingCurlyBrace | Token::ClosingBracket | Token::Colon | Token::Comma), span)) => {
+                self.error(TokenError::Msg(format!("Unexpected Start of File -> `{:?}`", token)), span)?;
+                V::null()
+            },
+            Some((token, span)) => {
+                let json = self.parse_value(token, span)?;
+                self.record(span.start);
+                json
+            },
+            None => {
+                self.error(TokenError::Msg("Could not tokenize JSON".to_string()), Span { start: 0, end: 0 })?;
+                V::null()
+            }
+        };
+
+        if let Some((token, span)) = self.next_token() {
+            self.error(TokenError::Msg(format!("Unexpected Value after end of document -> `{:?}`", token)), span)?;
+            while self.next_token().is_some() {}
+        }
+
+        Ok(json)
+    }
+
+    /// Takes the spans recorded by a parser made with `record_spans`
+    pub fn take_spans(&mut self) -> Option<Map<String, Span>> {
+        self.spans.take()
+    }
+
+    /// Takes the errors recorded by a parser made with `recover_errors`
+    pub fn take_diagnostics(&mut self) -> SpannedErrors {
+        self.diagnostics.take().unwrap_or_default()
+    }
+
+    fn next_token(&mut self) -> Option<(&'t Token<'a>, Span)> {
+        let (token, span) = self.tokens.next()?;
+        self.end = span.end;
+        Some((token, *span))
+    }
+
+    /// Returns the error, or records it when recovering
+    fn error(&mut self, error: TokenError, span: Span) -> Result<(), TokenError> {
+        match self.diagnostics.as_mut() {
+            Some(diagnostics) => {
+                diagnostics.push((error, span));
+                Ok(())
+            },
+            None => Err(error)
+        }
+    }
+
+    /// Whether the next token closes an enclosing container of the other kind
+    fn closes_outer(&mut self, closing: &Token<'a>) -> Option<Span> {
+        let open = match closing {
+            Token::ClosingBracket => self.open_arrays,
+            _ => self.open_objects
+        };
+        if self.diagnostics.is_none() || open == 0 {
+            return None
+        }
+
+        match self.tokens.peek() {
+            Some((token, span)) if core::mem::discriminant(token) == core::mem::discriminant(closing) => Some(*span),
+            _ => None
+        }
+    }
+
+    /// Skips tokens until the next `,`, `}` or `]` that isn't nested inside the skipped tokens
+    fn synchronize(&mut self, skipped: &Token<'a>) {
+        let mut depth = match skipped {
+            Token::OpeningCurlyBrace | Token::OpeningBracket => 1,
+            _ => 0
+        };
+
+        while let Some((token, _)) = self.tokens.peek() {
+            match token {
+                Token::OpeningCurlyBrace | Token::OpeningBracket => depth += 1,
+                Token::ClosingCurlyBrace | Token::ClosingBracket | Token::Comma if depth == 0 => return,
+                Token::ClosingCurlyBrace | Token::ClosingBracket => depth -= 1,
+                _ => {}
+            }
+            self.next_token();
+        }
+    }
+
+    fn parse_value<V: Build<'a>>(&mut self, token: &Token<'a>, span: Span) -> Result<V, TokenError> {
+        match token {
+            Token::Integer(_) | Token::Unsigned(_) | Token::Float(_) if self.exact_numbers.is_some() => {
+                let source = self.exact_numbers.unwrap_or_default();
+                Ok(V::number(Number::from_literal(&source[span.start..span.end])))
             },
-            None => Err(TokenError::Msg("Could not tokenize JSON".to_string()))
+            Token::OpeningCurlyBrace | Token::OpeningBracket if self.too_deep(token, span)? => Ok(V::null()),
+            Token::OpeningCurlyBrace => Ok(V::object(self.parse_object()?)),
+            Token::OpeningBracket => Ok(V::array(self.parse_array()?)),
+            Token::String(ref string_val) => Ok(V::string(string_val.clone())),
+            Token::Boolean(bool_val) => Ok(V::boolean(*bool_val)),
+            Token::Integer(int_val) => Ok(V::integer(*int_val)),
+            Token::Unsigned(uint_val) => Ok(V::unsigned(*uint_val)),
+            Token::Float(float_val) if !float_val.is_finite() => {
+                self.error(TokenError::FloatTokenError("number out of range".to_string()), span)?;
+                Ok(V::null())
+            },
+            Token::Float(float_val) => Ok(V::float(*float_val)),
+            Token::Null => Ok(V::null()),
+            _ => Err(TokenError::Msg(format!("Unexpected Value -> `{:?}`", token)))
+        }
+    }
+
+    fn enter<S: ToString>(&mut self, segment: S) {
+        if self.spans.is_some() {
+            self.path.push(segment.to_string());
         }
     }
 
-    fn parse_array(&mut self) -> Result<Vec<JSON>, TokenError> {
+    fn leave(&mut self, start: usize) {
+        if self.spans.is_some() {
+            self.record(start);
+            self.path.pop();
+        }
+    }
+
+    fn record(&mut self, start: usize) {
+        let end = self.end;
+        if let Some(spans) = self.spans.as_mut() {
+            spans.insert(self.path.join("."), Span { start, end });
+        }
+    }
+
+    /// Whether the array or object `token` opens goes past `MAX_DEPTH`.
+    /// When recovering, the error is recorded and the container is skipped.
+    fn too_deep(&mut self, token: &Token<'a>, span: Span) -> Result<bool, TokenError> {
+        match check_depth(self.open_arrays + self.open_objects) {
+            Ok(()) => Ok(false),
+            Err(err) => {
+                self.error(err, span)?;
+                self.synchronize(token);
+                Ok(true)
+            }
+        }
+    }
+
+    fn parse_array<V: Build<'a>>(&mut self) -> Result<Vec<V>, TokenError> {
         let mut arr= Vec::new();
         let mut next_val = vec!["arr value", "]"];
+        self.open_arrays += 1;
+
+        loop {
+            if let Some(span) = self.closes_outer(&Token::ClosingCurlyBrace) {
+                self.error(TokenError::Msg("Unexpected Value `}`".to_string()), span)?;
+                break
+            }
+
+            let (token, span) = match self.next_token() {
+                Some(token) => token,
+                None => {
+                    let end = Span { start: self.end, end: self.end };
+                    self.error(TokenError::Msg("Unexpected End of File -> expected `]`".to_string()), end)?;
+                    break
+                }
+            };
 
-        while let Some(token) = self.tokens.next() {
             match token {
-                Token::OpeningCurlyBrace =>
-                    if next_val.contains(&"arr value") {
-                        next_val = vec![",", "]"];
-                        arr.push(JSON::Object(self.parse_object()?))
-                    } else {
-                        return Err(TokenError::Msg(format!("Expected Value -> `{:?}`", next_val)))
-                    },
-                Token::ClosingCurlyBrace => return Err(TokenError::Msg("Unexpected Value `}`".to_string())),
-                Token::ClosingBracket =>
-                    if next_val.contains(&"]") {
-                        break
-                    } else {
-                        return Err(TokenError::Msg(format!("Expected Value -> `{:?}`", next_val)));
-                    },
-                Token::OpeningBracket => {
-                    if next_val.contains(&"arr value") {
-                        next_val = vec![",", "]"];
-                    } else {
-                        return Err(TokenError::Msg(format!("Expected Value -> `{:?}`", next_val)))
-                    }
-                    arr.push(JSON::Array(self.parse_array()?))
-                },
-                Token::String(ref string_val) => {
-                    if next_val.contains(&"arr value") {
-                        next_val = vec![",", "]"];
-                    } else {
-                        return Err(TokenError::Msg(format!("Expected Value -> `{:?}`", next_val)))
+                Token::ClosingCurlyBrace => self.error(TokenError::Msg("Unexpected Value `}`".to_string()), span)?,
+                Token::ClosingBracket => {
+                    if !next_val.contains(&"]") {
+                        self.error(TokenError::Msg(format!("Expected Value -> `{:?}`", next_val)), span)?;
                     }
-                    arr.push(JSON::String(string_val.to_owned()))
+                    break
                 },
-                Token::Boolean(bool_val) => {
-                    if next_val.contains(&"arr_value") {
-                        next_val = vec![",", "]"];
+                Token::Comma => {
+                    if next_val.contains(&",") {
+                        next_val = match self.mode {
+                            Mode::Strict => vec!["arr value"],
+                            Mode::JSONC => vec!["arr value", "]"]
+                        };
                     } else {
-                        return Err(TokenError::Msg(format!("Expected Value -> `{:?}`", next_val)))
+                        self.error(TokenError::Msg(format!("Expected Value -> `{:?}`", next_val)), span)?;
                     }
-                    arr.push(JSON::Boolean(*bool_val))
                 },
-                Token::Integer(int_val) => {
-                    if next_val.contains(&"arr value") {
-                        next_val = vec![",", "]"];
-                    } else {
-                        return Err(TokenError::Msg(format!("Expected Value -> `{:?}`", next_val)))
-                    }
-                    arr.push(JSON::Integer(*int_val))
+                Token::Colon => {
+                    self.error(TokenError::Msg("Unexpected Value -> `:`".to_string()), span)?;
+                    self.synchronize(token);
+                    next_val = vec![",", "]"];
                 },
-                Token::Float(float_val) => {
+                _ => {
                     if next_val.contains(&"arr value") {
                         next_val = vec![",", "]"];
                     } else {
-                        return Err(TokenError::Msg(format!("Expected Value -> `{:?}`", next_val)))
+                        self.error(TokenError::Msg(format!("Expected Value -> `{:?}`", next_val)), span)?;
+                        self.synchronize(token);
+                        continue
                     }
-                    arr.push(JSON::Float(*float_val))
-                },
-                Token::Null => {
-                    if next_val.contains(&"arr value") {
-                        next_val = vec![",", "]"];
-                    } else {
-                        return Err(TokenError::Msg(format!("Expected Value -> `{:?}`", next_val)))
-                    }
-                    arr.push(JSON::Null)
-                },
-                Token::Comma => {
-                    if next_val.contains(&",") {
-                        next_val = vec!["arr value"];
-                    } else {
-                        return Err(TokenError::Msg(format!("Expected Value -> `{:?}`", next_val)))
-                    }
-                },
-                Token::Colon => return Err(TokenError::Msg("Unexpected Value -> `:`".to_string()))
+                    self.enter(arr.len());
+                    let value = self.parse_value(token, span)?;
+                    self.leave(span.start);
+                    arr.push(value)
+                }
             };
         }
 
+        self.open_arrays -= 1;
         Ok(arr)
     }
 
-    fn parse_object(&mut self) -> Result<HashMap<String, JSON>, TokenError> {
-        let mut object: HashMap<String, JSON> = HashMap::new();
-        let mut current_keyword = String::new();
+    fn parse_object<V: Build<'a>>(&mut self) -> Result<Map<V::Key, V>, TokenError> {
+        let mut object = Map::new();
+        let mut current_keyword = Cow::Borrowed("");
         let mut next_val = vec!["obj keyword", "}"];
+        self.open_objects += 1;
+
+        loop {
+            if let Some(span) = self.closes_outer(&Token::ClosingBracket) {
+                self.error(TokenError::Msg("Unexpected Value -> `]`".to_string()), span)?;
+                break
+            }
+
+            let (token, span) = match self.next_token() {
+                Some(token) => token,
+                None => {
+                    let end = Span { start: self.end, end: self.end };
+                    self.error(TokenError::Msg("Unexpected End of File -> expected `}`".to_string()), end)?;
+                    break
+                }
+            };
 
-        while let Some(token) = self.tokens.next() {
             match token {
-                Token::OpeningCurlyBrace =>
-                    if next_val.contains(&"obj value") {
-                        next_val = vec![",", "}"];
-                        object.insert(current_keyword.clone(), JSON::Object(self.parse_object()?));
-                    } else {
-                        return Err(TokenError::Msg(format!("Expected Value -> `{:?}`", next_val)))
-                    },
-                Token::ClosingCurlyBrace =>
-                    if next_val.contains(&"}") {
-                        break
-                    } else {
-                        return Err(TokenError::Msg(format!("Expected Value -> `{:?}`", next_val)))
-                    },
-                Token::ClosingBracket => return Err(TokenError::Msg("Unexpected Value -> `}`".to_string())),
-                Token::OpeningBracket =>
-                    if next_val.contains(&"obj value") {
-                        next_val = vec![",", "}"];
-                        object.insert(current_keyword.clone(), JSON::Array(self.parse_array()?));
-                    } else {
-                        return Err(TokenError::Msg(format!("Expected Value -> `{:?}`", next_val)))
-                    },
-                Token::String(string_val) =>
-                    if next_val.contains(&"obj keyword") {
-                        next_val = vec![":"];
-                        current_keyword = string_val.to_owned()
-                    } else if next_val.contains(&"obj value") {
-                        next_val = vec![",", "}"];
-                        object.insert(current_keyword.clone(), JSON::String(string_val.to_owned()));
-                    } else {
-                        return Err(TokenError::Msg(format!("Expected Value -> `{:?}`", next_val)))
-                    },
-                Token::Boolean(bool_val) =>
-                    if next_val.contains(&"obj value") {
-                        next_val = vec![",", "}"];
-                        object.insert(current_keyword.clone(), JSON::Boolean(*bool_val));
+                Token::ClosingCurlyBrace => {
+                    if !next_val.contains(&"}") {
+                        self.error(TokenError::Msg(format!("Expected Value -> `{:?}`", next_val)), span)?;
+                    }
+                    break
+                },
+                Token::ClosingBracket => self.error(TokenError::Msg("Unexpected Value -> `]`".to_string()), span)?,
+                Token::String(string_val) if next_val.contains(&"obj keyword") => {
+                    next_val = vec![":"];
+                    current_keyword = string_val.clone()
+                },
+                Token::Comma => {
+                    if !next_val.contains(&",") {
+                        self.error(TokenError::Msg(format!("Expected Value -> `{:?}`", next_val)), span)?;
+                    }
+                    next_val = match self.mode {
+                        Mode::Strict => vec!["obj keyword"],
+                        Mode::JSONC => vec!["obj keyword", "}"]
+                    };
+                },
+                Token::Colon =>
+                    if next_val.contains(&":") {
+                        next_val = vec!["obj value"];
                     } else {
-                        return Err(TokenError::Msg(format!("Expected Value -> `{:?}`", next_val)))
-                    },
-                Token::Integer(int_val) =>
-                    if next_val.contains(&"obj value") {
+                        self.error(TokenError::Msg(format!("Expected Value -> `{:?}`", next_val)), span)?;
+                        self.synchronize(token);
                         next_val = vec![",", "}"];
-                        object.insert(current_keyword.clone(), JSON::Integer(*int_val));
-                    } else {
-                        return Err(TokenError::Msg(format!("Expected Value -> `{:?}`", next_val)))
                     },
-                Token::Float(float_val) =>
+                _ =>
                     if next_val.contains(&"obj value") {
                         next_val = vec![",", "}"];
-                        object.insert(current_keyword.clone(), JSON::Float(*float_val));
+                        self.enter(&current_keyword);
+                        let value = self.parse_value(token, span)?;
+                        self.leave(span.start);
+                        object.insert(V::key(current_keyword.clone()), value);
                     } else {
-                        return Err(TokenError::Msg(format!("Expected Value -> `{:?}`", next_val)))
-                    },
-                Token::Null =>
-                    if next_val.contains(&"obj value") {
+                        self.error(TokenError::Msg(format!("Expected Value -> `{:?}`", next_val)), span)?;
+                        self.synchronize(token);
                         next_val = vec![",", "}"];
-                        object.insert(current_keyword.clone(), JSON::Null);
-                    } else {
-                        return Err(TokenError::Msg(format!("Expected Value -> `{:?}`", next_val)))
-                    },
-                Token::Comma =>
-                    if next_val.contains(&",") {
-                        next_val = vec!["obj keyword"];
-                    } else {
-                        return Err(TokenError::Msg(format!("Expected Value -> `{:?}`", next_val)))
-                    },
-                Token::Colon =>
-                    if next_val.contains(&":") {
-                        next_val = vec!["obj value"];
-                    } else {
-                        return Err(TokenError::Msg(format!("Expected Value -> `{:?}`", next_val)))
                     }
             }
         }
 
+        self.open_objects -= 1;
         Ok(object)
     }
 }
 
-impl<'a> Tokenize<'a> for Peekable<Chars<'a>> {
-    type TokenVec = Vec<Token>;
+impl<'a> Cursor<'a> {
+    pub fn new(source: &'a str) -> Self {
+        Cursor::with_scanner(source, Scanner::detect())
+    }
 
-    fn tokens(&mut self) -> Result<Self::TokenVec, TokenError> {
-        let mut token_vec: Vec<Token> = Vec::new();
-
-        'tokenizer: while let Some(character) = self.next() {
-            match character {
-                '{' => token_vec.push(Token::OpeningCurlyBrace),
-                '}' => token_vec.push(Token::ClosingCurlyBrace),
-                '[' => token_vec.push(Token::OpeningBracket),
-                ']' => token_vec.push(Token::ClosingBracket),
-                ',' => token_vec.push(Token::Comma),
-                ':' => token_vec.push(Token::Colon),
-                '"' => token_vec.push(self.tokenize_string()?),
-                '0'..='9' => token_vec.push(self.tokenize_number(character)?),
-                'a'..='z' => token_vec.push(self.tokenize_value(character)?),
-                _ =>
-                    if character.is_whitespace() {
-                        continue 'tokenizer
-                    } else {
-                        return Err(TokenError::UnknownChar(character.to_string()))
-                    }
-            };
+    /// Makes a cursor that skips through strings and whitespace with `scanner`
+    pub fn with_scanner(source: &'a str, scanner: Scanner) -> Self {
+        Self {
+            source,
+            base: 0,
+            chars: source.char_indices().peekable(),
+            scanner
         }
+    }
 
-        Ok(token_vec.clone())
+    /// Byte offset of the next character, or the source length once everything is consumed
+    pub fn offset(&mut self) -> usize {
+        match self.chars.peek() {
+            Some((index, _)) => self.base + *index,
+            None => self.source.len()
+        }
     }
 
-    fn tokenize_number(&mut self, first_char: char) -> Result<Token, TokenError> {
-        let mut number: String = first_char.to_string();
-        let mut is_float = false;
-        while let Some(character) = self.peek() {
-            match character {
-                '0'..='9' => number.push(*character),
-                '.' => { is_float = true; number.push(*character); },
-                _ => break
+    /// Moves the cursor forward to `offset`, which has to be on a character boundary
+    fn seek(&mut self, offset: usize) {
+        if offset != self.offset() {
+            self.base = offset;
+            self.chars = self.source[offset..].char_indices().peekable();
+        }
+    }
+
+    pub fn peek(&mut self) -> Option<&char> {
+        self.chars.peek().map(|(_, character)| character)
+    }
+
+    /// Reads the next token or run of trivia. After an error the cursor is past the bad input,
+    /// so lexing can carry on from there.
+    pub fn next_lexeme(&mut self, mode: Mode) -> Option<Result<Lexeme<'a>, TokenError>> {
+        let start = self.offset();
+        let character = self.next()?;
+
+        let token = match character {
+            '{' => Token::OpeningCurlyBrace,
+            '}' => Token::ClosingCurlyBrace,
+            '[' => Token::OpeningBracket,
+            ']' => Token::ClosingBracket,
+            ',' => Token::Comma,
+            ':' => Token::Colon,
+            '"' => match self.tokenize_string() {
+                Ok(token) => token,
+                Err(err) => return Some(Err(err))
+            },
+            '-' | '0'..='9' => match self.tokenize_number(character) {
+                Ok(token) => token,
+                Err(err) => return Some(Err(err))
+            },
+            '/' if mode == Mode::JSONC => {
+                return Some(self.skip_comment().map(|_| Lexeme::Comment(Span { start, end: self.offset() })))
+            },
+            _ if is_whitespace(character) => {
+                let offset = self.scanner.skip_whitespace(self.source.as_bytes(), self.offset());
+                self.seek(offset);
+                return Some(Ok(Lexeme::Whitespace(Span { start, end: self.offset() })))
+            },
+            _ if character.is_alphabetic() => match self.tokenize_value(character) {
+                Ok(token) => token,
+                Err(err) => return Some(Err(err))
+            },
+            _ => return Some(Err(TokenError::UnknownChar(character.to_string())))
+        };
+
+        Some(Ok(Lexeme::Token(token, Span { start, end: self.offset() })))
+    }
+
+    /// Tokenizes everything it can, collecting the errors with their spans instead of stopping
+    pub fn tokens_recovering(&mut self, mode: Mode) -> (Vec<(Token<'a>, Span)>, SpannedErrors) {
+        let mut tokens = Vec::new();
+        let mut errors = Vec::new();
+
+        loop {
+            let start = self.offset();
+            match self.next_lexeme(mode) {
+                Some(Ok(Lexeme::Token(token, span))) => tokens.push((token, span)),
+                Some(Ok(_)) => {},
+                Some(Err(err)) => errors.push((err, Span { start, end: self.offset() })),
+                None => break
             }
+        }
+
+        (tokens, errors)
+    }
+}
+
+impl<'a> Iterator for Cursor<'a> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.chars.next().map(|(_, character)| character)
+    }
+}
+
+impl<'a> Tokenize<'a> for Cursor<'a> {
+    type TokenVec = Vec<(Token<'a>, Span)>;
+
+    fn tokens_with_mode(&mut self, mode: Mode) -> Result<Self::TokenVec, TokenError> {
+        let token_vec = self.lexemes(mode)?
+            .into_iter()
+            .filter_map(|lexeme| match lexeme {
+                Lexeme::Token(token, span) => Some((token, span)),
+                _ => None
+            })
+            .collect();
+
+        Ok(token_vec)
+    }
+
+    fn lexemes(&mut self, mode: Mode) -> Result<Vec<Lexeme<'a>>, TokenError> {
+        let mut lexemes: Vec<Lexeme> = Vec::new();
+        while let Some(lexeme) = self.next_lexeme(mode) {
+            lexemes.push(lexeme?);
+        }
+
+        Ok(lexemes)
+    }
+
+    fn skip_comment(&mut self) -> Result<(), TokenError> {
+        match self.next() {
+            Some('/') => {
+                for character in self.by_ref() {
+                    if character == '\n' {
+                        break
+                    }
+                }
+
+                Ok(())
+            },
+            Some('*') => {
+                let mut star = false;
+                for character in self.by_ref() {
+                    match character {
+                        '/' if star => return Ok(()),
+                        '*' => star = true,
+                        _ => star = false
+                    }
+                }
+
+                Err(TokenError::Msg("Unterminated block comment".to_string()))
+            },
+            Some(character) => Err(TokenError::UnknownChar(format!("/{}", character))),
+            None => Err(TokenError::UnknownChar("/".to_string()))
+        }
+    }
+
+    fn tokenize_number(&mut self, first_char: char) -> Result<Token<'a>, TokenError> {
+        let start = self.offset() - first_char.len_utf8();
+        while let Some('0'..='9' | '.' | 'e' | 'E' | '+' | '-') = self.peek() {
             self.next();
         }
 
-        match is_float {
+        let number = &self.source[start..self.offset()];
+        number.parse::<Number>()?;
+
+        match number.contains(['.', 'e', 'E']) {
             true => {
+                // Floats too big for an `f64` come out infinite, the parser rejects them
+                // unless it keeps their exact text with `exact_numbers`
                 let float = number.parse::<f64>();
                 match float {
                     Ok(num) => Ok(Token::Float(num)),
@@ -277,51 +632,139 @@ impl<'a> Tokenize<'a> for Peekable<Chars<'a>> {
                 }
             },
             false => {
+                // Integers too big for a u64 become the nearest float,
+                // the parser can still keep their exact text with `exact_numbers`
                 let int = number.parse::<i64>();
                 match int {
                     Ok(num) => Ok(Token::Integer(num)),
-                    Err(err) => Err(TokenError::IntTokenError(err.to_string()))
+                    Err(err) => match (number.parse::<u64>(), number.parse::<f64>()) {
+                        (Ok(num), _) => Ok(Token::Unsigned(num)),
+                        (_, Ok(num)) => Ok(Token::Float(num)),
+                        _ => Err(TokenError::IntTokenError(err.to_string()))
+                    }
                 }
             },
         }
     }
 
-    fn tokenize_string(&mut self) -> Result<Token, TokenError> {
-        let mut string: String = String::new();
-        let mut slash = false;
-        while let Some(character) = self.next() {
-            match character {
-                '"' => {
-                    match slash {
-                        true => { slash = false; string.push(character); },
-                        false => break
-                    }
-                },
-                '\\' => {
-                    string.push(character);
-                    slash = true;
+    fn tokenize_string(&mut self) -> Result<Token<'a>, TokenError> {
+        let start = self.offset();
+        let bytes = self.source.as_bytes();
+        let mut end = start;
+        loop {
+            end = self.scanner.find_quote_or_backslash(bytes, end);
+            match bytes.get(end) {
+                Some(b'"') => break,
+                Some(_) => {
+                    let escaped = self.source[end + 1..].chars().next().map_or(0, char::len_utf8);
+                    end = (end + 1 + escaped).min(bytes.len());
                 },
-                _ => string.push(character)
-            };
+                None => {
+                    self.seek(end);
+                    return Err(TokenError::Msg("Unexpected End of File -> unterminated string".to_string()))
+                }
+            }
         }
 
-        Ok(Token::String(string))
+        self.seek(end + 1);
+        unescape(&self.source[start..end]).map(Token::String)
     }
 
-    fn tokenize_value(&mut self, first_char: char) -> Result<Token, TokenError> {
-        let mut value: String = first_char.to_string();
+    /// Reads a whole word, so `True`, `nulls` and `trüe` are reported as one unknown value
+    fn tokenize_value(&mut self, first_char: char) -> Result<Token<'a>, TokenError> {
+        let start = self.offset() - first_char.len_utf8();
         while let Some(character) = self.peek() {
-            match character {
-                'a'..='z' => value.push(*character),
-                _ => break
+            if !character.is_alphanumeric() {
+                break
             }
+            self.next();
         }
 
-        match value.as_str() {
+        match &self.source[start..self.offset()] {
             "true" => Ok(Token::Boolean(true)),
             "false" => Ok(Token::Boolean(false)),
             "null" => Ok(Token::Null),
-            _ => Err(TokenError::ValueTokenError(value))
+            value => Err(TokenError::ValueTokenError(value.to_string()))
         }
     }
-}
\ No newline at end of file
+}
+
+/// Fails once another array or object inside `depth` open ones would go past `MAX_DEPTH`
+pub(crate) fn check_depth(depth: usize) -> Result<(), TokenError> {
+    match depth {
+        depth if depth >= MAX_DEPTH => Err(TokenError::Msg(format!("Nested too deep -> more than {} arrays and objects", MAX_DEPTH))),
+        _ => Ok(())
+    }
+}
+
+/// Whether `character` is one of the four characters JSON allows between tokens.
+/// Other Unicode whitespace, like U+00A0, is an unknown character.
+pub(crate) fn is_whitespace(character: char) -> bool {
+    matches!(character, ' ' | '\t' | '\n' | '\r')
+}
+
+/// Decodes the escapes in the text between a string's quotes, borrowing it when there are none.
+/// Control characters have to be escaped, and `\u` escapes of surrogates have to come in pairs.
+pub(crate) fn unescape(raw: &str) -> Result<Cow<'_, str>, TokenError> {
+    if let Some(character) = raw.chars().find(|character| *character < ' ') {
+        return Err(TokenError::Msg(format!("Unescaped control character in string -> `{}`", character.escape_default())))
+    }
+    if !raw.contains('\\') {
+        return Ok(Cow::Borrowed(raw))
+    }
+
+    let mut string = String::with_capacity(raw.len());
+    let mut chars = raw.chars();
+    while let Some(character) = chars.next() {
+        if character != '\\' {
+            string.push(character);
+            continue
+        }
+
+        let escaped = match chars.next() {
+            Some('"') => '"',
+            Some('\\') => '\\',
+            Some('/') => '/',
+            Some('b') => '\u{8}',
+            Some('f') => '\u{c}',
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some('t') => '\t',
+            Some('u') => {
+                let first = hex_escape(&mut chars)?;
+                let code = match first {
+                    0xD800..=0xDBFF => match (chars.next(), chars.next()) {
+                        (Some('\\'), Some('u')) => match hex_escape(&mut chars)? {
+                            second @ 0xDC00..=0xDFFF => 0x10000 + ((first - 0xD800) << 10) + (second - 0xDC00),
+                            _ => first
+                        },
+                        _ => first
+                    },
+                    _ => first
+                };
+                match char::from_u32(code) {
+                    Some(character) => character,
+                    None => return Err(TokenError::Msg(format!("Unpaired surrogate in string -> `\\u{:04X}`", first)))
+                }
+            },
+            Some(character) => return Err(TokenError::Msg(format!("Invalid escape in string -> `\\{}`", character))),
+            None => return Err(TokenError::Msg("Invalid escape in string -> `\\`".to_string()))
+        };
+        string.push(escaped);
+    }
+
+    Ok(Cow::Owned(string))
+}
+
+/// Reads the four hex digits of a `\u` escape
+fn hex_escape(chars: &mut Chars) -> Result<u32, TokenError> {
+    let mut code = 0;
+    for _ in 0..4 {
+        match chars.next().and_then(|character| character.to_digit(16)) {
+            Some(digit) => code = code * 16 + digit,
+            None => return Err(TokenError::Msg("Invalid escape in string -> `\\u` needs four hex digits".to_string()))
+        }
+    }
+
+    Ok(code)
+}
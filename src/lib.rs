@@ -4,7 +4,7 @@
 //! Also to make JSON parsing look nicer. In my opinion, but overall just made it to make it.
 //!
 //! This is an example JSON file
-//! ```
+//! ```json
 //! [
 //!     1, 2, 3, 4, 5.5,
 //!     {
@@ -17,10 +17,12 @@
 //! Here's how you would parse the JSON with my Parser.
 //! There are better ones out there, this is just for a learning experiment!
 //! ```
-//! let mut file = File::open("json_file").unwrap();
+//! # use std::{fs::File, io::Read, str::FromStr};
+//! # use json_parser::{array, object, json::JSON};
+//! let mut file = File::open("src/test4.json").unwrap();
 //! let mut contents = String::new();
 //! file.read_to_string(&mut contents).unwrap();
-//! let arr = parser::Parser::new(contents.as_str()).parse();
+//! let arr = JSON::from_str(contents.as_str()).unwrap();
 //! assert_eq!(arr,
 //! array![
 //!     1, 2, 3, 4, 5.5,
@@ -30,13 +32,71 @@
 //!     10, 11, 12, 13, 14, 15
 //! ]);
 //! ```
+//!
+//! Everything but `parallel`, `writer` and `color` works without `std`, only `alloc` is needed. Turn off the default
+//! `std` feature to build for targets that don't have it, objects are then stored in a `BTreeMap`.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
 
 pub mod json;
 
 pub mod just;
 
+pub mod cst;
+
+pub mod spans;
+
+pub mod number;
+
+pub mod json_ref;
+
+pub mod raw;
+
+pub mod document;
+
+pub mod scan;
+
+#[cfg(feature = "std")]
+pub mod parallel;
+
+pub mod push;
+
+pub mod visit;
+
+pub mod pointer;
+
+pub mod patch;
+
+pub mod escape;
+
+#[cfg(feature = "std")]
+pub mod writer;
+
+#[cfg(feature = "std")]
+pub mod color;
+
 #[macro_use]
 mod macros;
 
+/// The parts of `alloc` the rest of the crate uses, which `std` would have in its prelude
+mod prelude {
+    pub use alloc::borrow::ToOwned;
+    pub use alloc::string::{String, ToString};
+    pub use alloc::vec::Vec;
+    pub use alloc::{format, vec};
+}
+
+/// What the exported macros need from `alloc`, so they also work in `no_std` crates
+#[doc(hidden)]
+pub mod __private {
+    pub use alloc::string::String;
+    pub use alloc::vec;
+    pub use alloc::vec::Vec;
+}
+
+mod canonical;
 mod expression;
+mod float;
 mod tests;
\ No newline at end of file
@@ -1,6 +1,7 @@
 /// # Examples
 ///
 /// ```
+/// # use json_parser::array;
 /// let arr1 = array![true, 10, "hello", 11.5];
 /// assert!(arr1[0] == true);
 /// assert!(arr1[1] == 10);
@@ -11,28 +12,24 @@
 /// arr2.insert(0, 0.into());
 /// arr2.insert(1, 1.into());
 ///
-/// assert!(arr[0] == 0);
-/// assert!(arr[1] == 1);
+/// assert!(arr2[0] == 0);
+/// assert!(arr2[1] == 1);
 ///
 /// let arr_val = arr1[3].get_float().unwrap();
-/// assert_eq(arr_val, String::from("hello");
+/// assert_eq!(arr_val, 11.5);
 /// ```
-
 #[macro_export]
 macro_rules! array {
     [] => {
         {
-            let vec: Vec<$crate::json::JSON> = Vec::new();
+            let vec: $crate::__private::Vec<$crate::json::JSON> = $crate::__private::Vec::new();
             vec
         }
     };
 
     [ $( $item:expr ),+ ] => {
         {
-            let mut vec = Vec::new();
-            $(
-                vec.push($item.into());
-            )*
+            let vec = $crate::__private::vec![$( $item.into() ),+];
             $crate::json::JSON::Array(vec)
         }
     };
@@ -42,16 +39,17 @@ macro_rules! array {
 /// # Examples
 ///
 /// ```
+/// # use json_parser::object;
 /// let obj = object!{
 ///     "Cringe" => true,
 ///     "Ugly" => 10,
 ///     "Death" => "Please"
 /// };
-
+///
 /// assert!(obj["Cringe"] == true);
 /// assert!(obj["Ugly"] == 10);
 /// assert!(obj["Death"] == "Please");
-
+///
 /// let cringe = obj["Cringe"].get_bool().unwrap();
 /// assert_eq!(cringe, true);
 /// ```
@@ -59,16 +57,16 @@ macro_rules! array {
 macro_rules! object {
     {} => {
         {
-            let hm: HashMap<String, $crate::json::JSON> = std::collections::HashMap::new();
+            let hm: $crate::json::Map<$crate::__private::String, $crate::json::JSON> = $crate::json::Map::new();
             hm
         }
     };
 
     { $( $key:expr => $value:expr ),+ } => {
         {
-            let mut hm = std::collections::HashMap::new();
+            let mut hm = $crate::json::Map::new();
             $(
-                hm.insert(String::from($key), $value.into());
+                hm.insert($crate::__private::String::from($key), $value.into());
             )*
             $crate::json::JSON::Object(hm)
         }
@@ -0,0 +1,208 @@
+//! # Number
+//!
+//! A JSON number kept exactly as it was written, for values that don't fit in an `i64` or `f64`.
+
+use core::convert::TryFrom;
+use core::fmt;
+use core::str::FromStr;
+
+use crate::prelude::*;
+use crate::just::TokenError;
+
+/// A number literal that round-trips exactly
+///
+/// # Example
+///
+/// ```
+/// # use json_parser::number::Number;
+/// let id: Number = "123456789012345678901".parse().unwrap();
+/// assert_eq!(id.as_i64(), None);
+/// assert_eq!(id.as_i128(), Some(123456789012345678901));
+/// assert_eq!(id.to_string(), "123456789012345678901");
+///
+/// let price: Number = "0.1".parse().unwrap();
+/// assert_eq!(price.as_decimal_str(), "0.1");
+/// assert_eq!(price.as_f64(), Some(0.1));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Number {
+    literal: String,
+}
+
+/// The value of a literal as `digits * 10^exponent`
+struct Decimal {
+    negative: bool,
+    digits: String,
+    exponent: i64,
+}
+
+impl Number {
+    /// Wraps a literal the tokenizer has already checked
+    pub(crate) fn from_literal(literal: &str) -> Self {
+        Self {
+            literal: literal.to_string()
+        }
+    }
+
+    /// The number exactly as it was written, which is always a valid decimal literal
+    pub fn as_decimal_str(&self) -> &str {
+        &self.literal
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        self.as_i128().and_then(|int_val| i64::try_from(int_val).ok())
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        self.as_i128().and_then(|int_val| u64::try_from(int_val).ok())
+    }
+
+    /// Returns the integer value, if the number is a whole number that fits.
+    /// `1.0` and `1e3` count as whole numbers.
+    pub fn as_i128(&self) -> Option<i128> {
+        let decimal = self.decimal();
+        if decimal.digits == "0" {
+            return Some(0)
+        }
+
+        let mut digits = decimal.digits.as_str();
+        let mut exponent = decimal.exponent;
+
+        while exponent < 0 {
+            digits = digits.strip_suffix('0')?;
+            exponent += 1;
+        }
+
+        let mut int_val: i128 = 0;
+        for digit in digits.chars() {
+            int_val = int_val.checked_mul(10)?.checked_add(digit.to_digit(10)? as i128)?;
+        }
+        for _ in 0..exponent {
+            int_val = int_val.checked_mul(10)?;
+        }
+
+        match decimal.negative {
+            true => int_val.checked_neg(),
+            false => Some(int_val)
+        }
+    }
+
+    /// Returns the nearest `f64`, unless the number is too large for one
+    pub fn as_f64(&self) -> Option<f64> {
+        match self.literal.parse::<f64>() {
+            Ok(float) if float.is_finite() => Some(float),
+            _ => None
+        }
+    }
+
+    fn decimal(&self) -> Decimal {
+        let literal = self.literal.as_str();
+        let (negative, unsigned) = match literal.strip_prefix('-') {
+            Some(unsigned) => (true, unsigned),
+            None => (false, literal)
+        };
+
+        let (mantissa, exponent) = match unsigned.find(['e', 'E']) {
+            Some(index) => {
+                let exponent = &unsigned[index + 1..];
+                let exponent = match exponent.parse::<i64>() {
+                    Ok(exponent) => exponent,
+                    Err(_) if exponent.starts_with('-') => i64::MIN / 2,
+                    Err(_) => i64::MAX / 2
+                };
+                (&unsigned[..index], exponent)
+            },
+            None => (unsigned, 0)
+        };
+
+        let (int_part, frac_part) = match mantissa.find('.') {
+            Some(index) => (&mantissa[..index], &mantissa[index + 1..]),
+            None => (mantissa, "")
+        };
+
+        let mut digits = format!("{}{}", int_part, frac_part).trim_start_matches('0').to_string();
+        if digits.is_empty() {
+            digits.push('0');
+        }
+
+        Decimal {
+            negative,
+            digits,
+            exponent: exponent - frac_part.len() as i64
+        }
+    }
+}
+
+/// Checks the literal against the JSON number grammar: `-? int frac? exp?`
+impl FromStr for Number {
+    type Err = TokenError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars().peekable();
+        let invalid = || TokenError::Msg(format!("Invalid number -> `{}`", s));
+
+        if chars.peek() == Some(&'-') {
+            chars.next();
+        }
+
+        match chars.next() {
+            Some('0') => {},
+            Some('1'..='9') => while let Some('0'..='9') = chars.peek() {
+                chars.next();
+            },
+            _ => return Err(invalid())
+        }
+
+        if chars.peek() == Some(&'.') {
+            chars.next();
+            if !matches!(chars.next(), Some('0'..='9')) {
+                return Err(invalid())
+            }
+            while let Some('0'..='9') = chars.peek() {
+                chars.next();
+            }
+        }
+
+        if let Some('e' | 'E') = chars.peek() {
+            chars.next();
+            if let Some('+' | '-') = chars.peek() {
+                chars.next();
+            }
+            if !matches!(chars.next(), Some('0'..='9')) {
+                return Err(invalid())
+            }
+            while let Some('0'..='9') = chars.peek() {
+                chars.next();
+            }
+        }
+
+        match chars.next() {
+            Some(_) => Err(invalid()),
+            None => Ok(Number::from_literal(s))
+        }
+    }
+}
+
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.literal)
+    }
+}
+
+impl From<i64> for Number {
+    fn from(item: i64) -> Self {
+        Number::from_literal(&item.to_string())
+    }
+}
+
+impl From<u64> for Number {
+    fn from(item: u64) -> Self {
+        Number::from_literal(&item.to_string())
+    }
+}
+
+impl From<i128> for Number {
+    fn from(item: i128) -> Self {
+        Number::from_literal(&item.to_string())
+    }
+}
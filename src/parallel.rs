@@ -0,0 +1,221 @@
+//! # Parallel
+//!
+//! Parses many independent records at once, one batch per thread, for NDJSON logs and
+//! documents that are one huge top level array.
+
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use crate::json::JSON;
+use crate::just::{self, Cursor, Mode, TokenError, JUST, Tokenize};
+use crate::scan::Scanner;
+use crate::spans::LineIndex;
+
+/// How many batches each thread gets on average, so a thread that draws slow records
+/// doesn't leave the others waiting
+const BATCHES_PER_THREAD: usize = 4;
+
+/// Splits input into records and parses them on several threads.
+/// Results come back in the order the records were written.
+///
+/// # Example
+///
+/// ```
+/// # use json_parser::{array, object};
+/// # use json_parser::parallel::ParallelParser;
+/// let parser = ParallelParser::new().threads(4);
+///
+/// let records = parser.parse_ndjson("{\"id\": 1}\n{\"id\": 2}\n\n{\"id\": }\n[3]\n");
+/// assert_eq!(records.len(), 4);
+/// assert_eq!(*records[0].as_ref().unwrap(), object!{"id" => 1});
+/// assert_eq!(records[2].as_ref().unwrap_err().line, 4);
+/// assert_eq!(*records[3].as_ref().unwrap(), array![3]);
+///
+/// let items = parser.parse_array(r#"[{"id": 1}, "two", 3, [4]]"#).unwrap();
+/// assert_eq!(items.into_iter().map(Result::unwrap).collect::<Vec<_>>(), vec![object!{"id" => 1}.into(), "two".into(), 3.into(), array![4]]);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct ParallelParser {
+    threads: usize,
+}
+
+/// A record that couldn't be parsed, and the line it started on
+#[derive(Debug)]
+pub struct RecordError {
+    pub line: usize,
+    pub error: TokenError,
+}
+
+/// A slice of the input holding one record, and where it starts
+struct Record<'a> {
+    text: &'a str,
+    offset: usize,
+}
+
+impl ParallelParser {
+    /// Uses as many threads as the machine has cores
+    pub fn new() -> Self {
+        let threads = thread::available_parallelism().map(|threads| threads.get()).unwrap_or(1);
+        Self {
+            threads
+        }
+    }
+
+    /// Sets how many threads to parse with
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Parses newline delimited JSON, one value per line. Blank lines are skipped.
+    pub fn parse_ndjson(&self, s: &str) -> Vec<Result<JSON, RecordError>> {
+        let mut records = Vec::new();
+        let mut offset = 0;
+        for line in s.split('\n') {
+            if !line.trim_matches(just::is_whitespace).is_empty() {
+                records.push(Record {
+                    text: line,
+                    offset
+                });
+            }
+            offset += line.len() + 1;
+        }
+
+        self.parse_records(s, &records)
+    }
+
+    /// Parses each item of a top level array on its own. Errors in an item are reported with
+    /// that item, the outer error is only for input that isn't an array at all.
+    pub fn parse_array(&self, s: &str) -> Result<Vec<Result<JSON, RecordError>>, TokenError> {
+        let records = split_array(s)?;
+        Ok(self.parse_records(s, &records))
+    }
+
+    fn parse_records(&self, source: &str, records: &[Record]) -> Vec<Result<JSON, RecordError>> {
+        let batch_size = (records.len() / (self.threads * BATCHES_PER_THREAD)).max(1);
+        let batches: Vec<&[Record]> = records.chunks(batch_size).collect();
+        let next_batch = AtomicUsize::new(0);
+        let finished = Mutex::new(Vec::with_capacity(batches.len()));
+
+        thread::scope(|scope| {
+            for _ in 0..self.threads.min(batches.len()) {
+                scope.spawn(|| loop {
+                    let index = next_batch.fetch_add(1, Ordering::Relaxed);
+                    let batch = match batches.get(index) {
+                        Some(batch) => batch,
+                        None => break
+                    };
+
+                    let results: Vec<Result<JSON, TokenError>> = batch.iter().map(|record| parse_record(record.text)).collect();
+                    finished.lock().expect("a parsing thread panicked").push((index, results));
+                });
+            }
+        });
+
+        let mut finished = finished.into_inner().expect("a parsing thread panicked");
+        finished.sort_by_key(|(index, _)| *index);
+
+        let lines = LineIndex::new(source);
+        finished.into_iter()
+            .flat_map(|(_, results)| results)
+            .zip(records)
+            .map(|(result, record)| result.map_err(|error| {
+                let leading = record.text.len() - record.text.trim_start_matches(just::is_whitespace).len();
+                RecordError {
+                    line: lines.position(record.offset + leading).line,
+                    error
+                }
+            }))
+            .collect()
+    }
+}
+
+impl Default for ParallelParser {
+    fn default() -> Self {
+        ParallelParser::new()
+    }
+}
+
+fn parse_record(text: &str) -> Result<JSON, TokenError> {
+    let x = Cursor::new(text).tokens_with_mode(Mode::Strict)?;
+    JUST::new(&x, Mode::Strict).parse()
+}
+
+/// Finds the items of a top level array from the positions of its structural characters,
+/// without tokenizing the items themselves. The positions are found block by block and
+/// the scan stops at the array's closing `]`.
+fn split_array(s: &str) -> Result<Vec<Record<'_>>, TokenError> {
+    let bytes = s.as_bytes();
+    let scanner = Scanner::detect();
+    let start = scanner.skip_whitespace(bytes, 0);
+    if bytes.get(start) != Some(&b'[') {
+        return Err(TokenError::Msg("Expected a top level array".to_string()))
+    }
+
+    let mut records = Vec::new();
+    let mut item_start = start + 1;
+    let mut closers = Vec::new();
+    let mut in_string = false;
+    let mut escaped = None;
+    let mut end = None;
+
+    for index in scanner.structural_indices(bytes) {
+        if index < start || escaped == Some(index) {
+            continue
+        }
+
+        match bytes[index] {
+            b'"' => in_string = !in_string,
+            b'\\' if in_string => escaped = Some(index + 1),
+            _ if in_string => {},
+            b'[' => closers.push(b']'),
+            b'{' => closers.push(b'}'),
+            b']' | b'}' => {
+                if closers.pop() != Some(bytes[index]) {
+                    return Err(TokenError::Msg(format!("Unexpected Value -> `{}`", bytes[index] as char)))
+                }
+                if closers.is_empty() {
+                    end = Some(index);
+                    break
+                }
+            },
+            b',' if closers.len() == 1 => {
+                records.push(Record {
+                    text: &s[item_start..index],
+                    offset: item_start
+                });
+                item_start = index + 1;
+            },
+            _ => {}
+        }
+    }
+
+    let end = match end {
+        Some(end) => end,
+        None => return Err(TokenError::Msg("Unexpected End of File -> expected `]`".to_string()))
+    };
+
+    let last = &s[item_start..end];
+    if !records.is_empty() || !last.trim_matches(just::is_whitespace).is_empty() {
+        records.push(Record {
+            text: last,
+            offset: item_start
+        });
+    }
+
+    if scanner.skip_whitespace(bytes, end + 1) != bytes.len() {
+        return Err(TokenError::Msg("Unexpected Value after end of document".to_string()))
+    }
+
+    Ok(records)
+}
+
+impl fmt::Display for RecordError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.error)
+    }
+}
+
+impl std::error::Error for RecordError {}
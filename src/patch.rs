@@ -0,0 +1,180 @@
+//! # Patch
+//!
+//! JSON Patch from RFC 6902: a list of `add`, `remove`, `replace`, `move`, `copy` and `test`
+//! operations, each finding its target with a JSON Pointer.
+
+use core::fmt;
+
+use crate::prelude::*;
+use crate::json::JSON;
+use crate::pointer;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PatchError {
+    /// The patch isn't a valid RFC 6902 document, like an unknown `op`, a missing member or a bad pointer
+    Invalid(String),
+
+    /// A pointer that has to point at something doesn't
+    NotFound(String),
+
+    /// A `test` operation found a different value
+    TestFailed(String),
+}
+
+impl JSON {
+    /// Applies an RFC 6902 patch, an array of operations. Either every operation applies or,
+    /// when one fails, the value is left as it was.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::str::FromStr;
+    /// # use json_parser::{array, object};
+    /// # use json_parser::json::JSON;
+    /// # use json_parser::patch::PatchError;
+    /// let mut json = object!{"name" => "ann", "tags" => array!["a"]};
+    /// let patch = JSON::from_str(r#"[
+    ///     {"op": "test", "path": "/name", "value": "ann"},
+    ///     {"op": "add", "path": "/tags/-", "value": "b"},
+    ///     {"op": "move", "from": "/name", "path": "/user"}
+    /// ]"#).unwrap();
+    /// json.apply_patch(&patch).unwrap();
+    /// assert_eq!(json, object!{"user" => "ann", "tags" => array!["a", "b"]});
+    ///
+    /// let failing = JSON::from_str(r#"[
+    ///     {"op": "remove", "path": "/tags"},
+    ///     {"op": "test", "path": "/user", "value": "bob"}
+    /// ]"#).unwrap();
+    /// assert!(matches!(json.apply_patch(&failing), Err(PatchError::TestFailed(_))));
+    /// assert_eq!(json, object!{"user" => "ann", "tags" => array!["a", "b"]});
+    /// ```
+    pub fn apply_patch(&mut self, patch: &JSON) -> Result<(), PatchError> {
+        let operations = match *patch {
+            JSON::Array(ref operations) => operations,
+            _ => return Err(PatchError::Invalid("A patch has to be an array of operations".to_string()))
+        };
+
+        let mut patched = self.clone();
+        for operation in operations {
+            apply(&mut patched, operation)?;
+        }
+
+        *self = patched;
+        Ok(())
+    }
+}
+
+fn apply(root: &mut JSON, operation: &JSON) -> Result<(), PatchError> {
+    let op = member(operation, "op")?;
+    let path = pointer_member(operation, "path")?;
+    match op.get_string().as_deref() {
+        Some("add") => add(root, path, member(operation, "value")?.clone()),
+        Some("remove") => remove(root, path).map(|_| ()),
+        Some("replace") => {
+            let value = member(operation, "value")?.clone();
+            let target = root.pointer_mut(path).ok_or_else(|| PatchError::NotFound(path.to_string()))?;
+            *target = value;
+            Ok(())
+        },
+        Some("move") => {
+            let from = pointer_member(operation, "from")?;
+            if path.starts_with(from) && path[from.len()..].starts_with('/') {
+                return Err(PatchError::Invalid(format!("Can't move `{}` into its own child `{}`", from, path)))
+            }
+            let value = remove(root, from)?;
+            add(root, path, value)
+        },
+        Some("copy") => {
+            let from = pointer_member(operation, "from")?;
+            let value = root.pointer(from).ok_or_else(|| PatchError::NotFound(from.to_string()))?.clone();
+            add(root, path, value)
+        },
+        Some("test") => {
+            let expected = member(operation, "value")?;
+            match root.pointer(path) {
+                Some(actual) if actual == expected => Ok(()),
+                Some(actual) => Err(PatchError::TestFailed(format!("`{}` is {}, not {}", path, actual, expected))),
+                None => Err(PatchError::NotFound(path.to_string()))
+            }
+        },
+        _ => Err(PatchError::Invalid(format!("Unknown operation -> `{}`", op)))
+    }
+}
+
+fn member<'a>(operation: &'a JSON, key: &str) -> Result<&'a JSON, PatchError> {
+    match *operation {
+        JSON::Object(ref json_obj) => json_obj.get(key)
+            .ok_or_else(|| PatchError::Invalid(format!("Operation is missing `{}` -> `{}`", key, operation))),
+        _ => Err(PatchError::Invalid(format!("Operation isn't an object -> `{}`", operation)))
+    }
+}
+
+/// Reads the pointer in `key` of an operation, checking it's a valid one
+fn pointer_member<'a>(operation: &'a JSON, key: &str) -> Result<&'a str, PatchError> {
+    match *member(operation, key)? {
+        JSON::String(ref path) if pointer::parse(path).is_some() => Ok(path),
+        ref other => Err(PatchError::Invalid(format!("`{}` isn't a JSON Pointer -> `{}`", key, other)))
+    }
+}
+
+/// Splits `path` into the container that holds its target and the target's key or index
+fn parent<'a>(root: &'a mut JSON, path: &str) -> Result<(&'a mut JSON, String), PatchError> {
+    let mut tokens = pointer::parse(path).ok_or_else(|| PatchError::Invalid(path.to_string()))?;
+    let last = tokens.pop().ok_or_else(|| PatchError::Invalid("The document itself has no parent".to_string()))?;
+    let parent = tokens.iter()
+        .try_fold(root, |value, token| pointer::child_mut(value, token))
+        .ok_or_else(|| PatchError::NotFound(path.to_string()))?;
+
+    Ok((parent, last))
+}
+
+fn add(root: &mut JSON, path: &str, value: JSON) -> Result<(), PatchError> {
+    if path.is_empty() {
+        *root = value;
+        return Ok(())
+    }
+
+    let (parent, last) = parent(root, path)?;
+    match *parent {
+        JSON::Object(ref mut json_obj) => {
+            json_obj.insert(last, value);
+            Ok(())
+        },
+        JSON::Array(ref mut json_arr) => {
+            let index = match last.as_str() {
+                "-" => json_arr.len(),
+                _ => pointer::index(&last).filter(|index| *index <= json_arr.len())
+                    .ok_or_else(|| PatchError::NotFound(path.to_string()))?
+            };
+            json_arr.insert(index, value);
+            Ok(())
+        },
+        _ => Err(PatchError::NotFound(path.to_string()))
+    }
+}
+
+fn remove(root: &mut JSON, path: &str) -> Result<JSON, PatchError> {
+    let (parent, last) = parent(root, path)?;
+    let removed = match *parent {
+        JSON::Object(ref mut json_obj) => json_obj.remove(&last),
+        JSON::Array(ref mut json_arr) => pointer::index(&last)
+            .filter(|index| *index < json_arr.len())
+            .map(|index| json_arr.remove(index)),
+        _ => None
+    };
+
+    removed.ok_or_else(|| PatchError::NotFound(path.to_string()))
+}
+
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            PatchError::Invalid(ref msg) => write!(f, "Invalid patch -> {}", msg),
+            PatchError::NotFound(ref path) => write!(f, "Nothing at `{}`", path),
+            PatchError::TestFailed(ref msg) => write!(f, "Test failed -> {}", msg)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PatchError {}
@@ -0,0 +1,139 @@
+//! # Pointer
+//!
+//! JSON Pointers from RFC 6901, like `/items/0/id`, which can reach any key the dot paths
+//! of `JSON::get` can't: keys with dots in them, keys made of digits and empty keys.
+
+use alloc::borrow::Cow;
+
+use crate::prelude::*;
+use crate::json::JSON;
+use crate::visit::Segment;
+
+impl JSON {
+    /// Returns the value `pointer` points at, or `None` if there isn't one or the pointer isn't valid.
+    /// `""` points at the whole document.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use json_parser::{array, object};
+    /// let json = object!{"items" => array![object!{"id/kind" => "video", "a.b" => 1, "" => 2}]};
+    /// assert_eq!(*json.pointer("/items/0/id~1kind").unwrap(), "video");
+    /// assert_eq!(*json.pointer("/items/0/a.b").unwrap(), 1);
+    /// assert_eq!(*json.pointer("/items/0/").unwrap(), 2);
+    /// assert_eq!(json.pointer("/items/1"), None);
+    /// assert_eq!(json.pointer("items"), None);
+    /// ```
+    pub fn pointer(&self, pointer: &str) -> Option<&JSON> {
+        parse(pointer)?.iter().try_fold(self, |value, token| child(value, token))
+    }
+
+    /// Returns the value `pointer` points at to change, or `None` if there isn't one or the pointer isn't valid
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use json_parser::{array, object};
+    /// let mut json = object!{"items" => array![1, 2]};
+    /// *json.pointer_mut("/items/1").unwrap() = "two".into();
+    /// assert_eq!(json, object!{"items" => array![1, "two"]});
+    /// ```
+    pub fn pointer_mut(&mut self, pointer: &str) -> Option<&mut JSON> {
+        parse(pointer)?.iter().try_fold(self, |value, token| child_mut(value, token))
+    }
+}
+
+/// Builds the pointer to the value at `path`, like the path `JSON::walk` hands its visitor
+///
+/// # Example
+///
+/// ```
+/// # use json_parser::{array, object};
+/// # use json_parser::json::JSON;
+/// # use json_parser::pointer;
+/// # use json_parser::visit::{Segment, Walk};
+/// let json = object!{"a/b" => array![object!{"~" => true}]};
+/// let mut found = String::new();
+/// json.walk(&mut |path: &[Segment], value: &JSON| {
+///     if *value == true {
+///         found = pointer::from_path(path);
+///     }
+///     Walk::Continue
+/// });
+/// assert_eq!(found, "/a~1b/0/~0");
+/// assert_eq!(*json.pointer(&found).unwrap(), true);
+/// ```
+pub fn from_path(path: &[Segment<'_>]) -> String {
+    let mut pointer = String::new();
+    for segment in path {
+        pointer.push('/');
+        match *segment {
+            Segment::Key(key) => pointer.push_str(&escape(key)),
+            Segment::Index(index) => pointer.push_str(&index.to_string())
+        }
+    }
+
+    pointer
+}
+
+/// Escapes `~` as `~0` and `/` as `~1` so `key` can be one step of a pointer
+pub fn escape(key: &str) -> Cow<'_, str> {
+    match key.contains(['~', '/']) {
+        true => Cow::Owned(key.replace('~', "~0").replace('/', "~1")),
+        false => Cow::Borrowed(key)
+    }
+}
+
+/// Splits `pointer` into its unescaped reference tokens, or `None` if it isn't a valid pointer
+pub(crate) fn parse(pointer: &str) -> Option<Vec<String>> {
+    if pointer.is_empty() {
+        return Some(Vec::new())
+    }
+
+    pointer.strip_prefix('/')?
+        .split('/')
+        .map(unescape)
+        .collect()
+}
+
+fn unescape(token: &str) -> Option<String> {
+    let mut unescaped = String::with_capacity(token.len());
+    let mut chars = token.chars();
+    while let Some(character) = chars.next() {
+        match character {
+            '~' => match chars.next()? {
+                '0' => unescaped.push('~'),
+                '1' => unescaped.push('/'),
+                _ => return None
+            },
+            _ => unescaped.push(character)
+        }
+    }
+
+    Some(unescaped)
+}
+
+/// Reads `token` as an array index, which has to be `0` or digits without a leading zero
+pub(crate) fn index(token: &str) -> Option<usize> {
+    match token.as_bytes() {
+        [b'0'] => Some(0),
+        [b'1'..=b'9', rest @ ..] if rest.iter().all(u8::is_ascii_digit) => token.parse().ok(),
+        _ => None
+    }
+}
+
+pub(crate) fn child<'a>(value: &'a JSON, token: &str) -> Option<&'a JSON> {
+    match *value {
+        JSON::Object(ref json_obj) => json_obj.get(token),
+        JSON::Array(ref json_arr) => json_arr.get(index(token)?),
+        _ => None
+    }
+}
+
+pub(crate) fn child_mut<'a>(value: &'a mut JSON, token: &str) -> Option<&'a mut JSON> {
+    match *value {
+        JSON::Object(ref mut json_obj) => json_obj.get_mut(token),
+        JSON::Array(ref mut json_arr) => json_arr.get_mut(index(token)?),
+        _ => None
+    }
+}
@@ -0,0 +1,198 @@
+//! # PushParser
+//!
+//! Parses JSON that arrives a chunk at a time, like from a socket, handing back each
+//! value as soon as its last byte is fed.
+
+use core::str;
+
+use crate::prelude::*;
+use crate::json::JSON;
+use crate::just::{Mode, TokenError};
+
+/// Takes bytes in chunks of any size and returns every complete top level value.
+/// Chunks can split anything, strings, escapes and multi-byte characters included.
+/// A number or literal only ends at the byte after it, so one that ends the input is
+/// handed back by `finish`.
+///
+/// # Example
+///
+/// ```
+/// # use json_parser::{array, object};
+/// # use json_parser::just::Mode;
+/// # use json_parser::push::PushParser;
+/// let mut parser = PushParser::new(Mode::Strict);
+/// assert!(parser.feed(br#"{"name": "caf"#).is_empty());
+/// assert!(parser.feed(&[0xc3]).is_empty());
+/// let values = parser.feed(&[0xa9, b'"', b'}', b'[', b'1']);
+/// assert_eq!(values.len(), 1);
+/// assert_eq!(*values[0].as_ref().unwrap(), object!{"name" => "café"});
+/// assert_eq!(parser.feed(b", 2]\n").into_iter().map(Result::unwrap).collect::<Vec<_>>(), vec![array![1, 2]]);
+/// let values = parser.feed(b"\"[\" 4");
+/// assert_eq!(*values[0].as_ref().unwrap(), "[");
+/// assert!(parser.feed(b"2").is_empty());
+/// assert_eq!(parser.finish().unwrap().unwrap(), 42);
+/// ```
+#[derive(Debug, Clone)]
+pub struct PushParser {
+    mode: Mode,
+    buffer: Vec<u8>,
+    scanned: usize,
+    start: usize,
+    depth: usize,
+    state: State,
+    junk: bool,
+}
+
+/// Where the scanner is, so it can pick up from the same place when the next chunk comes
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum State {
+    Structure,
+    Scalar,
+    String,
+    Escape,
+    Slash,
+    LineComment,
+    BlockComment,
+    BlockCommentStar,
+}
+
+impl PushParser {
+    pub fn new(mode: Mode) -> Self {
+        Self {
+            mode,
+            buffer: Vec::new(),
+            scanned: 0,
+            start: 0,
+            depth: 0,
+            state: State::Structure,
+            junk: false
+        }
+    }
+
+    /// Adds the next chunk of input and returns the values it completed, in order.
+    /// A value that fails to parse is returned as its error, and the parser carries on after it.
+    pub fn feed(&mut self, bytes: &[u8]) -> Vec<Result<JSON, TokenError>> {
+        self.buffer.extend_from_slice(bytes);
+        let mut values = Vec::new();
+        let mut consumed = 0;
+        let mut position = self.scanned;
+
+        while let Some(&byte) = self.buffer.get(position) {
+            position += 1;
+            self.state = match (self.state, byte) {
+                (State::String, b'"') => {
+                    if self.depth == 0 {
+                        values.push(self.parse(self.start, position));
+                        consumed = position;
+                    }
+                    State::Structure
+                },
+                (State::String, b'\\') => State::Escape,
+                (State::String, _) | (State::Escape, _) => State::String,
+                (State::Scalar, _) if ends_scalar(byte) => {
+                    values.push(self.parse(self.start, position - 1));
+                    position -= 1;
+                    consumed = position;
+                    State::Structure
+                },
+                (State::Scalar, _) => State::Scalar,
+                (State::Slash, b'/') => State::LineComment,
+                (State::Slash, b'*') => State::BlockComment,
+                (State::Slash, _) => {
+                    position -= 1;
+                    if self.depth == 0 {
+                        values.push(Err(TokenError::UnknownChar("/".to_string())));
+                        consumed = position;
+                    }
+                    State::Structure
+                },
+                (State::LineComment, b'\n') => State::Structure,
+                (State::LineComment, _) => State::LineComment,
+                (State::BlockComment, b'*') | (State::BlockCommentStar, b'*') => State::BlockCommentStar,
+                (State::BlockCommentStar, b'/') => State::Structure,
+                (State::BlockComment, _) | (State::BlockCommentStar, _) => State::BlockComment,
+                (State::Structure, b'"') => {
+                    if self.depth == 0 {
+                        self.start = position - 1;
+                        self.junk = false;
+                    }
+                    State::String
+                },
+                (State::Structure, b'/') if self.mode == Mode::JSONC => State::Slash,
+                (State::Structure, b'{') | (State::Structure, b'[') => {
+                    if self.depth == 0 {
+                        self.start = position - 1;
+                        self.junk = false;
+                    }
+                    self.depth += 1;
+                    State::Structure
+                },
+                (State::Structure, b'}') | (State::Structure, b']') if self.depth > 0 => {
+                    self.depth -= 1;
+                    if self.depth == 0 {
+                        values.push(self.parse(self.start, position));
+                        consumed = position;
+                    }
+                    State::Structure
+                },
+                (State::Structure, b' ') | (State::Structure, b'\t') | (State::Structure, b'\n') | (State::Structure, b'\r') => {
+                    if self.depth == 0 {
+                        self.junk = false;
+                        consumed = position;
+                    }
+                    State::Structure
+                },
+                (State::Structure, _) if self.depth == 0 && !ends_scalar(byte) => {
+                    self.start = position - 1;
+                    self.junk = false;
+                    State::Scalar
+                },
+                (State::Structure, _) => {
+                    if self.depth == 0 {
+                        if !self.junk {
+                            values.push(Err(TokenError::Msg(format!("Unexpected Start of File -> `{}`", byte as char))));
+                            self.junk = true;
+                        }
+                        consumed = position;
+                    }
+                    State::Structure
+                }
+            };
+        }
+
+        self.buffer.drain(..consumed);
+        self.start = self.start.saturating_sub(consumed);
+        self.scanned = self.buffer.len();
+        values
+    }
+
+    /// Ends the input and returns the number or literal it ended with, if it did.
+    /// Fails if the input stopped partway through a value or comment.
+    pub fn finish(self) -> Result<Option<JSON>, TokenError> {
+        match self.state {
+            _ if self.depth > 0 => Err(TokenError::Msg("Unexpected End of File -> unfinished value".to_string())),
+            State::Structure | State::LineComment => Ok(None),
+            State::Scalar => self.parse(self.start, self.buffer.len()).map(Some),
+            State::String | State::Escape => Err(TokenError::Msg("Unexpected End of File -> unterminated string".to_string())),
+            State::BlockComment | State::BlockCommentStar => Err(TokenError::Msg("Unterminated block comment".to_string())),
+            _ => Err(TokenError::UnknownChar("/".to_string()))
+        }
+    }
+
+    fn parse(&self, start: usize, end: usize) -> Result<JSON, TokenError> {
+        let text = match str::from_utf8(&self.buffer[start..end]) {
+            Ok(text) => text,
+            Err(err) => return Err(TokenError::Msg(format!("Invalid UTF-8 -> {}", err)))
+        };
+
+        match self.mode {
+            Mode::Strict => text.parse(),
+            Mode::JSONC => JSON::from_jsonc(text)
+        }
+    }
+}
+
+/// Whether `byte` ends a top level number or literal
+fn ends_scalar(byte: u8) -> bool {
+    matches!(byte, b'{' | b'}' | b'[' | b']' | b',' | b':' | b'"' | b'/' | b' ' | b'\t' | b'\n' | b'\r')
+}
@@ -0,0 +1,255 @@
+//! # RawJSON
+//!
+//! JSON text that has been checked but not parsed, so one field can be pulled out of a large
+//! document without building all the others.
+
+use alloc::borrow::Cow;
+
+use crate::prelude::*;
+use crate::json::{Map, JSON};
+use crate::just::{self, Build, Cursor, Mode, Token, TokenError, JUST, Tokenize};
+use crate::number::Number;
+
+/// A validated JSON value that is only parsed when asked for
+///
+/// # Example
+///
+/// ```
+/// # use json_parser::{array, json::JSON};
+/// # use json_parser::raw::RawJSON;
+/// let raw = RawJSON::parse(r#"{
+///     "pageInfo": {"totalResults": 4249, "resultsPerPage": 5},
+///     "items": [{"kind": "youtube#video"}, {"kind": "youtube#channel"}]
+/// }"#).unwrap();
+/// assert_eq!(raw.get("pageInfo.totalResults"), Some(JSON::Integer(4249)));
+/// assert_eq!(raw.get("items.1.kind"), Some(JSON::from("youtube#channel")));
+/// assert_eq!(raw.get("items.#.kind"), Some(array!["youtube#video", "youtube#channel"]));
+/// assert_eq!(raw.get("pageInfo.missing"), None);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RawJSON<'a> {
+    source: &'a str,
+    mode: Mode,
+}
+
+impl<'a> RawJSON<'a> {
+    /// Checks that `s` is valid JSON without building any values
+    pub fn parse(s: &'a str) -> Result<RawJSON<'a>, TokenError> {
+        RawJSON::parse_with_mode(s, Mode::Strict)
+    }
+
+    /// Checks that `s` is valid JSONC without building any values
+    pub fn from_jsonc(s: &'a str) -> Result<RawJSON<'a>, TokenError> {
+        RawJSON::parse_with_mode(s, Mode::JSONC)
+    }
+
+    fn parse_with_mode(s: &'a str, mode: Mode) -> Result<RawJSON<'a>, TokenError> {
+        let x = Cursor::new(s).tokens_with_mode(mode)?;
+        JUST::new(&x, mode).parse_document::<()>()?;
+
+        let raw = RawJSON {
+            source: s,
+            mode
+        };
+        let start = raw.skip_trivia(0);
+        let end = raw.skip_value(start);
+
+        Ok(RawJSON {
+            source: &s[start..end],
+            mode
+        })
+    }
+
+    /// The text of the value, without the whitespace around it
+    pub fn as_str(&self) -> &'a str {
+        self.source
+    }
+
+    /// Builds the value that was selected with `get` or `get_raw`
+    pub fn to_json(&self) -> JSON {
+        let x = Cursor::new(self.source).tokens_with_mode(self.mode).expect("RawJSON is always valid");
+        match x.first() {
+            Some((Token::OpeningCurlyBrace | Token::OpeningBracket, _)) =>
+                JUST::new(&x, self.mode).parse().expect("RawJSON is always valid"),
+            Some((Token::String(string), _)) => JSON::String(string.to_string()),
+            Some((Token::Boolean(boolean), _)) => JSON::Boolean(*boolean),
+            Some((Token::Integer(int_val), _)) => JSON::Integer(*int_val),
+            Some((Token::Unsigned(uint_val), _)) => JSON::Unsigned(*uint_val),
+            Some((Token::Float(float), _)) => JSON::Float(*float),
+            _ => JSON::Null
+        }
+    }
+
+    /// Finds the value at a dot path of keys and indexes without parsing anything around it.
+    /// Siblings that aren't on the path are skipped by counting brackets.
+    pub fn get_raw<'b, S>(&self, path: S) -> Option<RawJSON<'a>>
+    where
+        S: Into<&'b str>
+    {
+        let path = path.into();
+        let mut raw = *self;
+        if path.is_empty() {
+            return Some(raw)
+        }
+
+        for segment in path.split('.') {
+            raw = raw.child(segment)?;
+        }
+
+        Some(raw)
+    }
+
+    /// Allows you get items with the same expressions as `JSON::get`, returning `None` when a key
+    /// or index on the path isn't there. Only the part of the document the expression needs is parsed.
+    /// Everything from the first `#` onwards runs on that part as a normal `JSON::get`.
+    pub fn get<'b, S>(&self, expression: S) -> Option<JSON>
+    where
+        S: Into<&'b str>
+    {
+        let expression = expression.into();
+        match expression.find('#') {
+            Some(index) => {
+                let raw = self.get_raw(expression[..index].trim_end_matches('.'))?;
+                Some(raw.to_json().get(&expression[index..]))
+            },
+            None => Some(self.get_raw(expression)?.to_json())
+        }
+    }
+
+    fn child(&self, segment: &str) -> Option<RawJSON<'a>> {
+        let bytes = self.source.as_bytes();
+        let (close, index) = match bytes.first() {
+            Some(b'{') => (b'}', None),
+            Some(b'[') => (b']', Some(segment.parse::<usize>().ok()?)),
+            _ => return None
+        };
+
+        // When a key is repeated the last one wins, the same as in `JSON`
+        let mut found = None;
+        let mut position = self.skip_trivia(1);
+        let mut count = 0;
+        while bytes.get(position) != Some(&close) {
+            let matches = match index {
+                Some(index) => count == index,
+                None => {
+                    let key_end = self.skip_value(position);
+                    let key = &self.source[position + 1..key_end - 1];
+                    position = self.skip_trivia(key_end);
+                    position = self.skip_trivia(position + 1);
+                    just::unescape(key).is_ok_and(|key| key == segment)
+                }
+            };
+
+            let end = self.skip_value(position);
+            if matches {
+                found = Some(RawJSON {
+                    source: &self.source[position..end],
+                    mode: self.mode
+                });
+                if index.is_some() {
+                    break
+                }
+            }
+
+            count += 1;
+            position = self.skip_trivia(end);
+            if bytes.get(position) == Some(&b',') {
+                position = self.skip_trivia(position + 1);
+            }
+        }
+
+        found
+    }
+
+    /// Returns the offset of the first byte after the value starting at `start`
+    fn skip_value(&self, start: usize) -> usize {
+        let bytes = self.source.as_bytes();
+        let mut position = start;
+        let mut depth = 0;
+
+        while let Some(byte) = bytes.get(position) {
+            match byte {
+                b'"' => position = self.skip_string(position),
+                b'{' | b'[' => {
+                    depth += 1;
+                    position += 1;
+                },
+                b'}' | b']' if depth > 0 => {
+                    depth -= 1;
+                    position += 1;
+                },
+                b'/' if depth > 0 => position = self.skip_trivia(position).max(position + 1),
+                _ if depth > 0 => position += 1,
+                b'}' | b']' | b',' | b'/' => return position,
+                _ if just::is_whitespace(*byte as char) => return position,
+                _ => {
+                    position += 1;
+                    continue
+                }
+            }
+
+            if depth == 0 {
+                return position
+            }
+        }
+
+        position
+    }
+
+    /// Returns the offset just past the closing quote of the string starting at `start`
+    fn skip_string(&self, start: usize) -> usize {
+        let bytes = self.source.as_bytes();
+        let mut position = start + 1;
+
+        while let Some(byte) = bytes.get(position) {
+            match byte {
+                b'"' => return position + 1,
+                b'\\' => position += 2,
+                _ => position += 1
+            }
+        }
+
+        position
+    }
+
+    /// Returns the offset of the next byte that isn't whitespace or a comment
+    fn skip_trivia(&self, start: usize) -> usize {
+        let bytes = self.source.as_bytes();
+        let mut position = start;
+
+        loop {
+            match (bytes.get(position), bytes.get(position + 1)) {
+                (Some(byte), _) if just::is_whitespace(*byte as char) => position += 1,
+                (Some(b'/'), Some(b'/')) if self.mode == Mode::JSONC => {
+                    while !matches!(bytes.get(position), Some(b'\n') | None) {
+                        position += 1;
+                    }
+                },
+                (Some(b'/'), Some(b'*')) if self.mode == Mode::JSONC => {
+                    position += 2;
+                    while !matches!((bytes.get(position), bytes.get(position + 1)), (Some(b'*'), Some(b'/')) | (None, _)) {
+                        position += 1;
+                    }
+                    position += 2;
+                },
+                _ => return position
+            }
+        }
+    }
+}
+
+/// Validating a `RawJSON` runs the parser without building anything
+impl<'a> Build<'a> for () {
+    type Key = ();
+
+    fn key(_: Cow<'a, str>) {}
+    fn string(_: Cow<'a, str>) {}
+    fn boolean(_: bool) {}
+    fn integer(_: i64) {}
+    fn unsigned(_: u64) {}
+    fn float(_: f64) {}
+    fn number(_: Number) {}
+    fn array(_: Vec<()>) {}
+    fn object(_: Map<(), ()>) {}
+    fn null() {}
+}
@@ -0,0 +1,252 @@
+//! # Scan
+//!
+//! Finds quotes, backslashes and structural characters many bytes at a time with SIMD.
+//! The tokenizer uses it to jump over string contents and whitespace instead of walking them,
+//! and `ParallelParser::parse_array` uses the structural characters to split an array
+//! without tokenizing it. SIMD is only used on x86_64, other targets scan one byte at a time.
+
+use crate::prelude::*;
+
+/// The bytes that delimit tokens
+const STRUCTURAL: &[u8] = b"\"\\{}[]:,";
+
+/// The bytes JSON treats as whitespace
+const WHITESPACE: &[u8] = b" \t\n\r";
+
+/// How many bytes the scalar scanner looks at per block
+const SCALAR_WIDTH: usize = 32;
+
+/// Finds bytes in bulk with the widest instructions the CPU has.
+/// Every scanner gives the same results, only the speed differs.
+///
+/// # Example
+///
+/// ```
+/// # use json_parser::scan::Scanner;
+/// let source = br#"{"name": "a \"b\"", "ids": [1, 2]}"#;
+/// let scanner = Scanner::detect();
+/// assert!(scanner.structural_indices(source).eq(Scanner::scalar().structural_indices(source)));
+/// assert_eq!(scanner.find_quote_or_backslash(source, 10), 12);
+/// assert_eq!(scanner.skip_whitespace(b"   \n\t[]", 0), 5);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Scanner {
+    kind: Kind,
+}
+
+/// Only handed out once the CPU is known to support it, which is what makes the SIMD paths safe
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    /// One byte at a time, on any CPU
+    Scalar,
+
+    /// 16 bytes at a time, on x86_64
+    #[cfg(target_arch = "x86_64")]
+    SSE2,
+
+    /// 32 bytes at a time, on x86_64 CPUs that support AVX2
+    #[cfg(target_arch = "x86_64")]
+    AVX2,
+}
+
+impl Scanner {
+    /// The fastest scanner the CPU supports. The CPU is only asked once, so this is cheap to call.
+    pub fn detect() -> Scanner {
+        #[cfg(target_arch = "x86_64")]
+        {
+            if has_avx2() {
+                return Scanner { kind: Kind::AVX2 }
+            }
+            if has_sse2() {
+                return Scanner { kind: Kind::SSE2 }
+            }
+        }
+
+        Scanner::scalar()
+    }
+
+    /// The scanner that works one byte at a time, on any CPU
+    pub fn scalar() -> Scanner {
+        Scanner {
+            kind: Kind::Scalar
+        }
+    }
+
+    /// Every scanner the CPU supports, slowest first
+    pub fn supported() -> Vec<Scanner> {
+        #[allow(unused_mut)]
+        let mut kinds = vec![Kind::Scalar];
+
+        #[cfg(target_arch = "x86_64")]
+        {
+            if has_sse2() {
+                kinds.push(Kind::SSE2);
+            }
+            if has_avx2() {
+                kinds.push(Kind::AVX2);
+            }
+        }
+
+        kinds.into_iter().map(|kind| Scanner { kind }).collect()
+    }
+
+    /// Returns the offset of the first `"` or `\` at or after `from`, or `bytes.len()` if there isn't one
+    pub fn find_quote_or_backslash(self, bytes: &[u8], from: usize) -> usize {
+        let mut start = from;
+        while start < bytes.len() {
+            let (mask, width) = self.block(bytes, start, b"\"\\");
+            if mask != 0 {
+                return start + mask.trailing_zeros() as usize
+            }
+            start += width;
+        }
+
+        bytes.len()
+    }
+
+    /// Returns the offset of the first byte at or after `from` that isn't a space, tab, `\n` or `\r`
+    pub fn skip_whitespace(self, bytes: &[u8], from: usize) -> usize {
+        let mut start = from;
+        while start < bytes.len() {
+            let (mask, width) = self.block(bytes, start, WHITESPACE);
+            let others = !mask & low_bits(width);
+            if others != 0 {
+                return start + others.trailing_zeros() as usize
+            }
+            start += width;
+        }
+
+        bytes.len()
+    }
+
+    /// Iterates over the offset of every `"`, `\`, `{`, `}`, `[`, `]`, `:` and `,` in `bytes`, in order.
+    /// Bytes inside strings are included, it's up to the caller to pair up the quotes.
+    /// Blocks are only scanned as the iterator reaches them, so stopping early skips the rest.
+    pub fn structural_indices(self, bytes: &[u8]) -> StructuralIndices<'_> {
+        StructuralIndices {
+            scanner: self,
+            bytes,
+            block_start: 0,
+            next_block: 0,
+            mask: 0
+        }
+    }
+
+    /// Bit `i` of the mask is set when `bytes[start + i]` is one of `needles`.
+    /// Also returns how many bytes the mask covers.
+    fn block(self, bytes: &[u8], start: usize, needles: &[u8]) -> (u32, usize) {
+        #[cfg(target_arch = "x86_64")]
+        {
+            let remaining = bytes.len() - start;
+            match self.kind {
+                // SAFETY: `supported` only makes these scanners when the CPU has the instructions,
+                // and the block lies inside `bytes`
+                Kind::AVX2 if remaining >= 32 => return (unsafe { x86::mask_avx2(&bytes[start..], needles) }, 32),
+                Kind::SSE2 if remaining >= 16 => return (unsafe { x86::mask_sse2(&bytes[start..], needles) }, 16),
+                _ => {}
+            }
+        }
+
+        let end = (start + SCALAR_WIDTH).min(bytes.len());
+        let mut mask = 0;
+        for (i, byte) in bytes[start..end].iter().enumerate() {
+            if needles.contains(byte) {
+                mask |= 1 << i;
+            }
+        }
+
+        (mask, end - start)
+    }
+}
+
+/// The offsets of the structural characters in some bytes, from `Scanner::structural_indices`
+#[derive(Debug, Clone)]
+pub struct StructuralIndices<'b> {
+    scanner: Scanner,
+    bytes: &'b [u8],
+    block_start: usize,
+    next_block: usize,
+    mask: u32,
+}
+
+impl Iterator for StructuralIndices<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while self.mask == 0 {
+            if self.next_block >= self.bytes.len() {
+                return None
+            }
+            let (mask, width) = self.scanner.block(self.bytes, self.next_block, STRUCTURAL);
+            self.block_start = self.next_block;
+            self.next_block += width;
+            self.mask = mask;
+        }
+
+        let index = self.block_start + self.mask.trailing_zeros() as usize;
+        self.mask &= self.mask - 1;
+        Some(index)
+    }
+}
+
+/// Whether the CPU has SSE2. `std` remembers the answer after the first time it asks.
+#[cfg(all(target_arch = "x86_64", feature = "std"))]
+fn has_sse2() -> bool {
+    is_x86_feature_detected!("sse2")
+}
+
+/// Whether the CPU has AVX2. `std` remembers the answer after the first time it asks.
+#[cfg(all(target_arch = "x86_64", feature = "std"))]
+fn has_avx2() -> bool {
+    is_x86_feature_detected!("avx2")
+}
+
+// Without `std` the CPU can't be asked, so only what the crate was compiled for is used
+#[cfg(all(target_arch = "x86_64", not(feature = "std")))]
+fn has_sse2() -> bool {
+    cfg!(target_feature = "sse2")
+}
+
+#[cfg(all(target_arch = "x86_64", not(feature = "std")))]
+fn has_avx2() -> bool {
+    cfg!(target_feature = "avx2")
+}
+
+/// A mask with the lowest `width` bits set
+fn low_bits(width: usize) -> u32 {
+    match width {
+        32 => u32::MAX,
+        _ => (1 << width) - 1
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+mod x86 {
+    use core::arch::x86_64::*;
+
+    /// Compares the first 16 bytes of `bytes` against every needle at once
+    #[target_feature(enable = "sse2")]
+    pub(super) unsafe fn mask_sse2(bytes: &[u8], needles: &[u8]) -> u32 {
+        debug_assert!(bytes.len() >= 16);
+        let block = _mm_loadu_si128(bytes.as_ptr() as *const __m128i);
+        let mut matches = _mm_setzero_si128();
+        for &needle in needles {
+            matches = _mm_or_si128(matches, _mm_cmpeq_epi8(block, _mm_set1_epi8(needle as i8)));
+        }
+
+        _mm_movemask_epi8(matches) as u32
+    }
+
+    /// Compares the first 32 bytes of `bytes` against every needle at once
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn mask_avx2(bytes: &[u8], needles: &[u8]) -> u32 {
+        debug_assert!(bytes.len() >= 32);
+        let block = _mm256_loadu_si256(bytes.as_ptr() as *const __m256i);
+        let mut matches = _mm256_setzero_si256();
+        for &needle in needles {
+            matches = _mm256_or_si256(matches, _mm256_cmpeq_epi8(block, _mm256_set1_epi8(needle as i8)));
+        }
+
+        _mm256_movemask_epi8(matches) as u32
+    }
+}
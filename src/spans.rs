@@ -0,0 +1,128 @@
+//! # Spans
+//!
+//! Where each value of a parsed document came from, so errors can point at a line and column.
+
+use core::fmt;
+
+use crate::prelude::*;
+use crate::json::Map;
+use crate::just::{Span, TokenError};
+
+/// A line and column in the source, both starting at 1. Columns count characters, not bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Where a value starts and ends in the source
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Location {
+    pub start: Position,
+    pub end: Position,
+}
+
+/// A syntax error and where it is in the source
+#[derive(Debug)]
+pub struct Diagnostic {
+    pub error: TokenError,
+    pub location: Location,
+}
+
+/// Turns byte offsets into lines and columns
+pub(crate) struct LineIndex<'a> {
+    source: &'a str,
+    line_starts: Vec<usize>,
+}
+
+/// The location of every value in a document, keyed by the same dot paths `JSON::get` takes.
+/// The root value is keyed by `""`.
+///
+/// # Example
+///
+/// ```
+/// # use json_parser::json::JSON;
+/// # use json_parser::just::Mode;
+/// let (json, spans) = JSON::parse_with_spans("{\n    \"items\": [1, 2.5]\n}", Mode::Strict).unwrap();
+/// assert_eq!(json.get("items.1"), 2.5);
+/// assert_eq!(spans.get("items.1").unwrap().to_string(), "line 2, col 18");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spans {
+    locations: Map<String, Location>,
+}
+
+impl Spans {
+    pub(crate) fn new(source: &str, spans: Map<String, Span>) -> Self {
+        let index = LineIndex::new(source);
+        let locations = spans.into_iter()
+            .map(|(path, span)| (path, index.location(span)))
+            .collect();
+
+        Self {
+            locations
+        }
+    }
+
+    /// Returns where the value at `path` was written, if there is one
+    pub fn get<'a, S>(&self, path: S) -> Option<&Location>
+    where
+        S: Into<&'a str>
+    {
+        self.locations.get(path.into())
+    }
+}
+
+impl<'a> LineIndex<'a> {
+    pub(crate) fn new(source: &'a str) -> Self {
+        let mut line_starts = vec![0];
+        for (index, character) in source.char_indices() {
+            if character == '\n' {
+                line_starts.push(index + 1);
+            }
+        }
+
+        Self {
+            source,
+            line_starts
+        }
+    }
+
+    pub(crate) fn position(&self, offset: usize) -> Position {
+        let line = match self.line_starts.binary_search(&offset) {
+            Ok(line) => line,
+            Err(line) => line - 1
+        };
+
+        Position {
+            line: line + 1,
+            column: self.source[self.line_starts[line]..offset].chars().count() + 1
+        }
+    }
+
+    pub(crate) fn location(&self, span: Span) -> Location {
+        Location {
+            start: self.position(span.start),
+            end: self.position(span.end)
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.location, self.error)
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, col {}", self.line, self.column)
+    }
+}
+
+/// Displays the start of the value, which is what an error message usually wants
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.start)
+    }
+}
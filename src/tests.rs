@@ -1,9 +1,12 @@
 #[cfg(test)]
 mod test {
-    use std::fs::File;
+    use std::fs::{self, File};
     use std::io::Read;
+    use std::process::Command;
     use std::str::FromStr;
-    use crate::json;
+    use std::borrow::Cow;
+    use crate::{cst, document, json, json_ref, number, push, raw, scan, spans};
+    use crate::just::{Cursor, Mode, Tokenize};
 
     #[test]
     fn test_get_1() {
@@ -96,7 +99,7 @@ mod test {
         test1.read_to_string(&mut contents).unwrap();
         let json = json::JSON::from_str(contents.as_str()).unwrap();
         eprintln!("\n{:?}\n", json);
-        assert_eq!(json["items"][0]["id"]["kind"].get_string().unwrap(), "youtube#video");
+        assert_eq!(json["items"][0]["id"]["kind"].get_string().unwrap(), "youtube#channel");
     }
 
     #[test]
@@ -207,4 +210,1060 @@ mod test {
             }
         });
     }
+
+    #[test]
+    fn test_jsonc() {
+        let mut test6 = File::open("src/test6.jsonc").unwrap();
+        let mut contents = String::new();
+        test6.read_to_string(&mut contents).unwrap();
+        let obj = json::JSON::from_jsonc(contents.as_str()).unwrap();
+        eprintln!("\n{:?}\n", obj);
+        assert_eq!(obj, object!{
+            "Hello" => "hi",
+            "Numbers" => array![1, 2, 3],
+            "Why" => object!{
+                "Because" => 1000,
+                "Url" => "http://localhost/*not a comment*/"
+            }
+        });
+
+        assert!(json::JSON::from_str(contents.as_str()).is_err());
+        assert!(json::JSON::from_str("[1, 2,]").is_err());
+        assert!(json::JSON::from_jsonc("[1, /* unterminated").is_err());
+        assert!(json::JSON::from_jsonc("[1, 2,,]").is_err());
+    }
+
+    #[test]
+    fn test_cst() {
+        let mut test6 = File::open("src/test6.jsonc").unwrap();
+        let mut contents = String::new();
+        test6.read_to_string(&mut contents).unwrap();
+        let mut cst = cst::CST::from_jsonc(contents.as_str()).unwrap();
+        assert_eq!(cst.to_string(), contents);
+
+        cst.set("Why.Because", 2000).unwrap();
+        assert_eq!(cst.to_string(), contents.replace("1000", "2000"));
+
+        cst.insert("Numbers.0", 0).unwrap();
+        cst.remove("Numbers.3").unwrap();
+        cst.insert("Why.Can", true).unwrap();
+        cst.remove("Hello").unwrap();
+        assert_eq!(cst.to_string(), r#"{
+    "Numbers": [0, 1, 2,], // trailing comma in an array
+    /*
+     * Block comments can span lines
+     */
+    "Why": {
+        "Because": 2000,
+        "Url": "http://localhost/*not a comment*/",
+        "Can": true,
+    },
+}
+"#);
+        assert_eq!(cst.to_json().unwrap()["Why"]["Can"], true);
+
+        let mut cst = cst::CST::from_str("[1,\n 2]").unwrap();
+        cst.remove("1").unwrap();
+        assert_eq!(cst.to_string(), "[1]");
+        cst.set("0", array![1, 2]).unwrap();
+        cst.insert("0.2", 3).unwrap();
+        assert_eq!(cst.to_string(), "[[1,2,3]]");
+        assert!(cst.set("0.5", 4).is_err());
+        assert!(cst.insert("0.0.0", 4).is_err());
+
+        let deep = format!("{}{}", "[".repeat(128), "]".repeat(128));
+        assert!(cst::CST::from_str(&deep).is_ok());
+        let too_deep = format!("{}{}", "[".repeat(129), "]".repeat(129));
+        let error = cst::CST::from_str(&too_deep).unwrap_err();
+        assert!(error.to_string().contains("Nested too deep"));
+        assert!(cst::CST::from_str(&"[{\"a\":".repeat(100_000)).is_err());
+
+        let nested = |levels: usize| (0..levels).fold(json::JSON::Null, |value, _| json::JSON::Array(vec![value]));
+        let mut cst = cst::CST::from_str("{\"a\": 1}").unwrap();
+        assert!(matches!(cst.set("a", nested(200)), Err(cst::EditError::TooDeep(_))));
+        assert!(matches!(cst.insert("b", nested(128)), Err(cst::EditError::TooDeep(_))));
+        cst.set("a", nested(127)).unwrap();
+        assert_eq!(cst.to_json().unwrap(), object!{"a" => nested(127)});
+
+        let mut cst = cst::CST::from_str("{\"a\": {\"x\": 1}, \"b\": 2, \"a\": {\"x\": 3}}").unwrap();
+        cst.set("a.x", 4).unwrap();
+        assert_eq!(cst.to_string(), "{\"a\": {\"x\": 1}, \"b\": 2, \"a\": {\"x\": 4}}");
+        cst.set("a", true).unwrap();
+        assert_eq!(cst.to_json().unwrap(), object!{"a" => true, "b" => 2});
+        cst.remove("a").unwrap();
+        assert_eq!(cst.to_string(), "{\"b\": 2}");
+    }
+
+    #[test]
+    fn test_spans() {
+        let mut test1 = File::open("src/test1.json").unwrap();
+        let mut contents = String::new();
+        test1.read_to_string(&mut contents).unwrap();
+        let (json, spans) = json::JSON::parse_with_spans(contents.as_str(), Mode::Strict).unwrap();
+        assert_eq!(json, json::JSON::from_str(contents.as_str()).unwrap());
+
+        let kind = spans.get("items.2.id.kind").unwrap();
+        assert_eq!(kind.start, spans::Position { line: 31, column: 23 });
+        assert_eq!(kind.end, spans::Position { line: 31, column: 38 });
+
+        let id = spans.get("items.1.id").unwrap();
+        assert_eq!(id.start, spans::Position { line: 22, column: 19 });
+        assert_eq!(id.end, spans::Position { line: 25, column: 14 });
+
+        assert_eq!(spans.get("pageInfo.totalResults").unwrap().to_string(), "line 7, col 25");
+        assert_eq!(spans.get("").unwrap().start, spans::Position { line: 1, column: 1 });
+        assert!(spans.get("items.3").is_none());
+    }
+
+    #[test]
+    fn test_recovering() {
+        let (json, diagnostics) = json::JSON::parse_recovering(r#"{
+    "a": 1,
+    "b": [1, 2 3],
+    "c" 5,
+    "d": {"e": true,
+    "f": ]
+}"#, Mode::Strict);
+        for diagnostic in diagnostics.iter() {
+            eprintln!("{}", diagnostic);
+        }
+        assert_eq!(json, object!{
+            "a" => 1,
+            "b" => array![1, 2],
+            "d" => object!{
+                "e" => true
+            }
+        });
+
+        let positions: Vec<(usize, usize)> = diagnostics.iter()
+            .map(|diagnostic| (diagnostic.location.start.line, diagnostic.location.start.column))
+            .collect();
+        assert_eq!(positions, vec![(3, 16), (4, 9), (6, 10), (7, 1), (7, 2)]);
+
+        let (json, diagnostics) = json::JSON::parse_recovering("[1, 2]", Mode::Strict);
+        assert_eq!(json, array![1, 2]);
+        assert!(diagnostics.is_empty());
+
+        assert!(json::JSON::from_str("[1, 2").is_err());
+        assert!(json::JSON::from_str("[1, 2] 3").is_err());
+
+        let too_deep = format!("[{}{}, 1]", "[".repeat(200), "]".repeat(200));
+        let (json, diagnostics) = json::JSON::parse_recovering(&too_deep, Mode::Strict);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].error.to_string().contains("Nested too deep"));
+        assert_eq!(diagnostics[0].location.start.column, 129);
+        assert_eq!(json[1], 1);
+        assert!(json::JSON::from_str(&too_deep).is_err());
+    }
+
+    #[test]
+    fn test_lexer() {
+        let source = "[\"é✓\", True,\u{a0}nulé, 𝄞, \"\\q\", false]";
+        let (tokens, errors) = Cursor::new(source).tokens_recovering(Mode::Strict);
+        let errors: Vec<(String, &str)> = errors.iter()
+            .map(|(error, span)| (error.to_string(), &source[span.start..span.end]))
+            .collect();
+        assert_eq!(errors, vec![
+            ("Unknown value `True`".to_string(), "True"),
+            ("Unknown character `\u{a0}`".to_string(), "\u{a0}"),
+            ("Unknown value `nulé`".to_string(), "nulé"),
+            ("Unknown character `𝄞`".to_string(), "𝄞"),
+            ("Invalid escape in string -> `\\q`".to_string(), "\"\\q\"")
+        ]);
+        assert_eq!(format!("{:?}", tokens.iter().map(|(token, _)| token).collect::<Vec<_>>()),
+            r#"[OpeningBracket, String("é✓"), Comma, Comma, Comma, Comma, Comma, Boolean(false), ClosingBracket]"#);
+
+        let (_, diagnostics) = json::JSON::parse_recovering(source, Mode::Strict);
+        let columns: Vec<usize> = diagnostics.iter().map(|diagnostic| diagnostic.location.start.column).collect();
+        for column in [8, 13, 14, 20, 23] {
+            assert!(columns.contains(&column), "{:?}", columns);
+        }
+
+        assert!(json::JSON::from_str("[1,\u{3000}2]").is_err());
+        assert!(json::JSON::from_str("[1,\u{c}2]").is_err());
+        assert_eq!(json::JSON::from_str(" \t\r\n[true,\r\nnull]\n").unwrap(), array![true, json::JSON::Null]);
+    }
+
+    #[test]
+    fn test_exact_numbers() {
+        let source = "[12345678901234567890123,0.1,1.50,3,[18446744073709551615]]";
+        let json = json::JSON::parse_exact_numbers(source, Mode::Strict).unwrap();
+        assert_eq!(json.to_string(), source);
+        assert_eq!(json[1].get_number().unwrap().as_f64(), Some(0.1));
+        assert_eq!(json[2].get_number().unwrap().as_decimal_str(), "1.50");
+        assert_eq!(json[3].get_number().unwrap().as_i64(), Some(3));
+        assert_eq!(json[4][0].get_number().unwrap().as_u64(), Some(u64::MAX));
+        assert_eq!(json[4][0].get_number().unwrap().as_i64(), None);
+
+        let lossy = json::JSON::from_str(source).unwrap();
+        assert_eq!(lossy[0], 12345678901234567890123.0);
+
+        let number = |literal: &str| literal.parse::<number::Number>().unwrap();
+        assert_eq!(number("1e3").as_i64(), Some(1000));
+        assert_eq!(number("-2.500e1").as_i128(), Some(-25));
+        assert_eq!(number("0e-7").as_u64(), Some(0));
+        assert_eq!(number("-1").as_u64(), None);
+        assert_eq!(number("1.5").as_i64(), None);
+        assert_eq!(number("1e400").as_f64(), None);
+        assert_eq!(number("1e400").as_i128(), None);
+        assert_eq!(number("170141183460469231731687303715884105727").as_i128(), Some(i128::MAX));
+        assert!("01".parse::<number::Number>().is_err());
+        assert!("1.".parse::<number::Number>().is_err());
+        assert!("-".parse::<number::Number>().is_err());
+        assert!("1e+".parse::<number::Number>().is_err());
+    }
+
+    #[test]
+    fn test_unsigned() {
+        let json = json::JSON::from_str(r#"{"id": 18446744073709551615, "small": 42, "over": 18446744073709551616}"#).unwrap();
+        assert_eq!(json["id"], json::JSON::Unsigned(u64::MAX));
+        assert!(json["id"] == 18446744073709551615u64);
+        assert!(json["id"] != 9223372036854775807i64);
+        assert_eq!(json["id"].get_unsigned(), Some(u64::MAX));
+        assert_eq!(json["id"].get_int(), None);
+        assert_eq!(json["id"].to_string(), "18446744073709551615");
+
+        assert_eq!(json["small"], json::JSON::Integer(42));
+        assert!(json["small"] == 42u64);
+        assert!(json["small"] == 42.0);
+        assert!(json["over"] == 18446744073709551616.0);
+
+        assert_eq!(json::JSON::from(42u64), json::JSON::Integer(42));
+        assert_eq!(json::JSON::from(u64::MAX), json::JSON::Unsigned(u64::MAX));
+        assert!(json::JSON::Float(2.5) != 2i64);
+        assert!(json::JSON::Float(9223372036854775808.0) != i64::MAX);
+
+        assert_eq!(json::JSON::Integer(2), json::JSON::Float(2.0));
+        assert_eq!(json::JSON::Float(2.0), json::JSON::Integer(2));
+        assert_eq!(json::JSON::Unsigned(1 << 63), json::JSON::Float(9223372036854775808.0));
+        assert_ne!(json::JSON::Integer(i64::MAX), json::JSON::Float(9223372036854775808.0));
+        assert_ne!(json::JSON::Integer(-1), json::JSON::Unsigned(u64::MAX));
+        assert_eq!(array![1, object!{"a" => 2.0}], array![1.0, object!{"a" => 2u64}]);
+        let exact = json::JSON::parse_exact_numbers("[1.0, 18446744073709551615, 0.5]", Mode::Strict).unwrap();
+        assert_eq!(exact, array![1, u64::MAX, 0.5]);
+        assert_eq!(array![1, u64::MAX, 0.5], exact);
+        assert_ne!(json::JSON::Integer(1), json::JSON::Boolean(true));
+
+        let mut test2 = File::open("src/test2.json").unwrap();
+        let mut contents = String::new();
+        test2.read_to_string(&mut contents).unwrap();
+        let json = json::JSON::from_str(contents.as_str()).unwrap();
+        assert!(json[0]["id"] == 877994604561387500u64);
+    }
+
+    #[test]
+    fn test_json_ref() {
+        let mut test1 = File::open("src/test1.json").unwrap();
+        let mut contents = String::new();
+        test1.read_to_string(&mut contents).unwrap();
+        let json = json::JSON::from_str(contents.as_str()).unwrap();
+        let json_ref = json_ref::JSONRef::parse(contents.as_str()).unwrap();
+
+        assert!(matches!(json_ref["kind"], json_ref::JSONRef::String(Cow::Borrowed(_))));
+        assert_eq!(json_ref["kind"].get_string(), Some("youtube#searchListResponse"));
+        assert_eq!(json_ref.get("pageInfo.totalResults").get_int(), Some(4249));
+        assert_eq!(json_ref.get("items.#").get_int(), Some(3));
+        assert_eq!(
+            json_ref.get("items.#(id.kind=='youtube#video')#.etag").into_owned(),
+            json.get("items.#(id.kind=='youtube#video')#.etag")
+        );
+        assert_eq!(json_ref.into_owned(), json);
+
+        let escaped = json_ref::JSONRef::parse(r#"["say \"hi\"", "\\"]"#).unwrap();
+        assert_eq!(escaped[0].get_string(), Some(r#"say "hi""#));
+        assert!(matches!(escaped[1], json_ref::JSONRef::String(Cow::Owned(_))));
+        assert_eq!(escaped.get_arr().map(|arr| arr.len()), Some(2));
+
+        let jsonc = json_ref::JSONRef::from_jsonc("{\"a\": [1, 2,], // trailing\n}").unwrap();
+        assert_eq!(jsonc["a"].get_arr().map(|arr| arr.len()), Some(2));
+    }
+
+    #[test]
+    fn test_raw() {
+        let mut test1 = File::open("src/test1.json").unwrap();
+        let mut contents = String::new();
+        test1.read_to_string(&mut contents).unwrap();
+        let json = json::JSON::from_str(contents.as_str()).unwrap();
+        let raw = raw::RawJSON::parse(contents.as_str()).unwrap();
+
+        assert_eq!(raw.get("pageInfo.totalResults"), Some(json.get("pageInfo.totalResults")));
+        assert_eq!(raw.get("items.2"), Some(json.get("items.2")));
+        assert_eq!(raw.get("items.#"), Some(json.get("items.#")));
+        assert_eq!(raw.get("items.#(id.kind=='youtube#video')#.etag"), Some(json.get("items.#(id.kind=='youtube#video')#.etag")));
+        assert_eq!(raw.get_raw("items.0.id.kind").map(|raw| raw.as_str()), Some("\"youtube#channel\""));
+        assert_eq!(raw.get("items.3"), None);
+        assert_eq!(raw.get("pageInfo.nope"), None);
+        assert_eq!(raw.get("kind.nope"), None);
+        assert_eq!(raw.to_json(), json);
+
+        let mut test6 = File::open("src/test6.jsonc").unwrap();
+        let mut contents = String::new();
+        test6.read_to_string(&mut contents).unwrap();
+        let json = json::JSON::from_jsonc(contents.as_str()).unwrap();
+        let raw = raw::RawJSON::from_jsonc(contents.as_str()).unwrap();
+        assert_eq!(raw.to_json(), json);
+        for (key, value) in json.get_obj().unwrap() {
+            assert_eq!(raw.get_raw(key.as_str()).map(|raw| raw.to_json()), Some(value));
+        }
+
+        assert!(raw::RawJSON::parse("[1, 2").is_err());
+        assert!(raw::RawJSON::parse(r#"{"a": [1, 2}"#).is_err());
+        assert!(raw::RawJSON::parse("[1] [2]").is_err());
+
+        let source = r#"{"a": 1, "b": {"c": 1}, "a": 2, "b": {"c": 2}}"#;
+        let raw = raw::RawJSON::parse(source).unwrap();
+        let json = json::JSON::from_str(source).unwrap();
+        assert_eq!(raw.get("a"), Some(json::JSON::Integer(2)));
+        assert_eq!(raw.get("b.c"), Some(json.get("b.c")));
+        assert_eq!(raw.to_json(), json);
+    }
+
+    #[test]
+    fn test_document() {
+        let mut test2 = File::open("src/test2.json").unwrap();
+        let mut contents = String::new();
+        test2.read_to_string(&mut contents).unwrap();
+        let json = json::JSON::from_str(contents.as_str()).unwrap();
+        let document = document::Document::parse(contents.as_str()).unwrap();
+
+        assert_eq!(document.to_json(), json);
+        assert_eq!(document::Document::from(&json).to_json(), json);
+        assert_eq!(document.root().len(), json.get_arr().map(|arr| arr.len()));
+
+        let tweet = document.root().at(0).unwrap();
+        assert_eq!(tweet.member("id").unwrap().get_int(), json[0]["id"].get_int());
+        assert_eq!(tweet.member("truncated").unwrap().get_bool(), Some(false));
+        assert_eq!(tweet.member("entities").unwrap().member("symbols").unwrap().is_empty(), Some(true));
+        assert_eq!(tweet.entries().unwrap().count(), json[0].get_obj().unwrap().len());
+        for (key, value) in tweet.entries().unwrap() {
+            assert_eq!(value.to_json(), json[0][key]);
+        }
+        assert_eq!(document.get("0.entities.hashtags.0.text"), Some(json::JSON::from("Angular")));
+        assert_eq!(document.get("0.entities.hashtags.#"), Some(json::JSON::Integer(1)));
+        assert_eq!(document.get("0.nope"), None);
+        assert!(tweet.member("nope").is_none());
+        assert!(tweet.at(0).is_none());
+
+        let jsonc = document::Document::from_jsonc("{\"a\": [1, {},], /* b */ \"b\": null,}").unwrap();
+        assert_eq!(jsonc.root().member("a").unwrap().len(), Some(2));
+        assert!(jsonc.root().member("b").unwrap().is_null());
+        assert!(document::Document::parse("[1, 2,]").is_err());
+
+        let source = r#"{"a": 1, "b": [true], "a": {"x": 2}, "c": null, "a": 3}"#;
+        let repeated = document::Document::parse(source).unwrap();
+        let json = json::JSON::from_str(source).unwrap();
+        assert_eq!(repeated.to_json(), json);
+        assert_eq!(repeated.get("a"), Some(json::JSON::Integer(3)));
+        assert_eq!(repeated.root().member("a").unwrap().get_int(), Some(3));
+        assert_eq!(repeated.root().len(), Some(3));
+        let keys: Vec<&str> = repeated.root().entries().unwrap().map(|(key, _)| key).collect();
+        assert_eq!(keys, ["b", "c", "a"]);
+        assert_eq!(document::Document::parse(r#"{"a": 1, "a": 2}"#).unwrap().get("a"), Some(json::JSON::Integer(2)));
+    }
+
+    /// Strings made of the characters the scanners look for, plus some that they don't
+    fn random_strings(count: usize) -> Vec<String> {
+        let alphabet = ['"', '\\', '{', '}', '[', ']', ':', ',', ' ', '\t', '\n', '\r', 'a', '1', 'é', '✓'];
+        let mut seed: u64 = 0x2545_f491_4f6c_dd1d;
+        let mut next = move || {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed
+        };
+
+        (0..count).map(|_| {
+            let len = (next() % 150) as usize;
+            (0..len).map(|_| alphabet[(next() % alphabet.len() as u64) as usize]).collect()
+        }).collect()
+    }
+
+    #[test]
+    fn test_scan() {
+        let scalar = scan::Scanner::scalar();
+        let mut sources = random_strings(500);
+        for path in ["src/test1.json", "src/test2.json", "src/test3.json", "src/test4.json", "src/test5.json", "src/test6.jsonc"] {
+            let mut file = File::open(path).unwrap();
+            let mut contents = String::new();
+            file.read_to_string(&mut contents).unwrap();
+            sources.push(contents);
+        }
+
+        assert_eq!(scan::Scanner::supported().last(), Some(&scan::Scanner::detect()));
+        for scanner in scan::Scanner::supported() {
+            for source in sources.iter() {
+                let bytes = source.as_bytes();
+                assert_eq!(scanner.structural_indices(bytes).collect::<Vec<_>>(), scalar.structural_indices(bytes).collect::<Vec<_>>(), "{:?} on {:?}", scanner, source);
+                for from in 0..=bytes.len() {
+                    assert_eq!(scanner.find_quote_or_backslash(bytes, from), scalar.find_quote_or_backslash(bytes, from));
+                    assert_eq!(scanner.skip_whitespace(bytes, from), scalar.skip_whitespace(bytes, from));
+                }
+
+                for mode in [Mode::Strict, Mode::JSONC] {
+                    assert_eq!(
+                        format!("{:?}", Cursor::with_scanner(source, scanner).lexemes(mode)),
+                        format!("{:?}", Cursor::with_scanner(source, scalar).lexemes(mode)),
+                        "{:?} on {:?}", scanner, source
+                    );
+                    assert_eq!(
+                        format!("{:?}", Cursor::with_scanner(source, scanner).tokens_recovering(mode)),
+                        format!("{:?}", Cursor::with_scanner(source, scalar).tokens_recovering(mode))
+                    );
+                }
+            }
+        }
+
+        let tokens = Cursor::new(r#"["a \"quoted\" é",   "\\"]"#).tokens().unwrap();
+        assert_eq!(format!("{:?}", tokens.iter().map(|(token, _)| token).collect::<Vec<_>>()),
+            r#"[OpeningBracket, String("a \"quoted\" é"), Comma, String("\\"), ClosingBracket]"#);
+        assert!(Cursor::new(r#"["unterminated \"]"#).tokens().is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_parallel() {
+        let mut test2 = File::open("src/test2.json").unwrap();
+        let mut contents = String::new();
+        test2.read_to_string(&mut contents).unwrap();
+        let tweet = json::JSON::from_str(contents.as_str()).unwrap()[0].clone();
+
+        let mut ndjson = String::new();
+        let mut array = String::from("[");
+        for i in 0..200 {
+            let record = match i % 50 {
+                7 => "{\"broken\": }".to_string(),
+                13 => "[\"a \\\"quoted\\\" ]\", \"b\"]".to_string(),
+                _ => format!("{{\"i\": {}, \"tweet\": {}}}", i, tweet)
+            };
+            ndjson.push_str(&record);
+            ndjson.push_str("\r\n");
+            if i > 0 {
+                array.push_str(",\n");
+            }
+            array.push_str(&record);
+        }
+        array.push(']');
+
+        for threads in [1, 3, 8] {
+            let parser = crate::parallel::ParallelParser::new().threads(threads);
+            let records = parser.parse_ndjson(&ndjson);
+            let items = parser.parse_array(&array).unwrap();
+            assert_eq!(records.len(), 200);
+            assert_eq!(items.len(), 200);
+
+            for (i, (record, item)) in records.iter().zip(items.iter()).enumerate() {
+                match i % 50 {
+                    7 => {
+                        assert_eq!(record.as_ref().unwrap_err().line, i + 1);
+                        assert_eq!(item.as_ref().unwrap_err().line, i + 1);
+                    },
+                    13 => {
+                        assert_eq!(record.as_ref().unwrap().get_arr().unwrap().len(), 2);
+                        assert_eq!(item.as_ref().unwrap().get_arr().unwrap().len(), 2);
+                    },
+                    _ => {
+                        assert_eq!(record.as_ref().unwrap()["i"], i as i64);
+                        assert_eq!(item.as_ref().unwrap()["i"], i as i64);
+                        assert_eq!(item.as_ref().unwrap()["tweet"]["user"], tweet["user"]);
+                    }
+                }
+            }
+        }
+
+        let parser = crate::parallel::ParallelParser::new();
+        assert!(parser.parse_array("{}").is_err());
+        assert!(parser.parse_array("[1, [2]").is_err());
+        assert!(parser.parse_array("[1] 2").is_err());
+        assert!(parser.parse_array("[1}").is_err());
+        assert!(parser.parse_array("[{]}").is_err());
+        assert!(parser.parse_array("[[1}, 2]").is_err());
+        assert_eq!(parser.parse_array(" [ ] ").unwrap().len(), 0);
+        assert_eq!(parser.parse_ndjson("\n\n").len(), 0);
+    }
+
+    /// Feeds `source` to a fresh push parser `chunk_size` bytes at a time
+    fn feed_in_chunks(source: &[u8], chunk_size: usize, mode: Mode) -> (Vec<Result<json::JSON, crate::just::TokenError>>, push::PushParser) {
+        let mut parser = push::PushParser::new(mode);
+        let mut values = Vec::new();
+        for chunk in source.chunks(chunk_size) {
+            values.extend(parser.feed(chunk));
+        }
+        (values, parser)
+    }
+
+    #[test]
+    fn test_push() {
+        for (path, mode) in [("src/test1.json", Mode::Strict), ("src/test2.json", Mode::Strict), ("src/test5.json", Mode::Strict), ("src/test6.jsonc", Mode::JSONC)] {
+            let mut file = File::open(path).unwrap();
+            let mut contents = String::new();
+            file.read_to_string(&mut contents).unwrap();
+            let expected = match mode {
+                Mode::Strict => json::JSON::from_str(contents.as_str()).unwrap(),
+                Mode::JSONC => json::JSON::from_jsonc(contents.as_str()).unwrap()
+            };
+
+            for chunk_size in [1, 2, 3, 7, 64, 4096] {
+                let (values, parser) = feed_in_chunks(contents.as_bytes(), chunk_size, mode);
+                assert_eq!(values.len(), 1, "{} in chunks of {}", path, chunk_size);
+                assert_eq!(*values[0].as_ref().unwrap(), expected);
+                assert!(parser.finish().is_ok());
+            }
+        }
+
+        let stream = "{\"emoji\": \"✓ é\", \"escape\": \"\\u00e9 \\\" ]\"}\n[1, [2, {\"a\": \"}\"}]]  {\"b\": null}";
+        for chunk_size in 1..=9 {
+            let (values, parser) = feed_in_chunks(stream.as_bytes(), chunk_size, Mode::Strict);
+            let values: Vec<json::JSON> = values.into_iter().map(Result::unwrap).collect();
+            assert_eq!(values.len(), 3);
+            assert_eq!(values[0]["emoji"], "✓ é");
+            assert_eq!(values[0]["escape"], "é \" ]");
+            assert_eq!(values[1][1][1]["a"], "}");
+            assert!(values[2]["b"].is_null());
+            assert!(parser.finish().is_ok());
+        }
+
+        let stream = "\"a[\" [1, \"]\"] 42 true{\"k\": null}-1.5e3 \"esc\\\"aped\"\n\"\"null";
+        for chunk_size in 1..=9 {
+            let (values, parser) = feed_in_chunks(stream.as_bytes(), chunk_size, Mode::Strict);
+            let mut values: Vec<json::JSON> = values.into_iter().map(Result::unwrap).collect();
+            values.extend(parser.finish().unwrap());
+            assert_eq!(values, vec!["a[".into(), array![1, "]"], 42.into(), true.into(), object!{"k" => json::JSON::Null}, (-1.5e3).into(), "esc\"aped".into(), "".into(), json::JSON::Null], "in chunks of {}", chunk_size);
+        }
+
+        let (values, parser) = feed_in_chunks(b"[1, 2,] true ]] [3] nul [4", 2, Mode::Strict);
+        assert_eq!(values.len(), 5);
+        assert!(values[0].is_err());
+        assert_eq!(*values[1].as_ref().unwrap(), true);
+        assert!(values[2].is_err());
+        assert_eq!(*values[3].as_ref().unwrap(), array![3]);
+        assert!(values[4].is_err());
+        assert!(parser.finish().is_err());
+
+        let (_, parser) = feed_in_chunks(b"\"open", 3, Mode::Strict);
+        assert!(parser.finish().is_err());
+
+        let (values, parser) = feed_in_chunks(b"[\"\xff\"]/", 1, Mode::JSONC);
+        assert!(values[0].is_err());
+        assert!(parser.finish().is_err());
+
+        let mut parser = push::PushParser::new(Mode::JSONC);
+        assert!(parser.feed(b"/[1,")[0].is_err());
+        assert_eq!(parser.feed(b"2]").into_iter().map(Result::unwrap).collect::<Vec<_>>(), vec![array![1, 2]]);
+
+        let (values, parser) = feed_in_chunks(b"// [not a value]\n[1] /* [2] */", 4, Mode::JSONC);
+        assert_eq!(values.len(), 1);
+        assert_eq!(*values[0].as_ref().unwrap(), array![1]);
+        assert!(parser.finish().is_ok());
+    }
+
+    #[test]
+    fn test_conformance() {
+        let decisions = fs::read_to_string("src/conformance/README.md").unwrap();
+        let mut names: Vec<String> = fs::read_dir("src/conformance").unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .filter(|name| name.ends_with(".json"))
+            .collect();
+        names.sort();
+        assert!(names.len() > 200);
+
+        for name in names {
+            let bytes = fs::read(format!("src/conformance/{}", name)).unwrap();
+            let parsed = std::str::from_utf8(&bytes).ok().map(|source| (json::JSON::from_str(source), json_ref::JSONRef::parse(source)));
+            let accepted = match parsed {
+                Some((Ok(json), Ok(json_ref))) => {
+                    assert_eq!(json_ref.into_owned(), json, "{}", name);
+                    assert_eq!(json::JSON::from_str(&json.to_string()).unwrap(), json, "{} round trip", name);
+                    true
+                },
+                Some((Err(_), Err(_))) | None => false,
+                Some(_) => panic!("{}: JSON and JSONRef disagree", name)
+            };
+
+            let expected = match &name[..2] {
+                "y_" => true,
+                "n_" => false,
+                _ => {
+                    let decision = decisions.lines()
+                        .find(|line| line.starts_with(&format!("| `{}` |", name)))
+                        .unwrap_or_else(|| panic!("{} has no documented decision", name));
+                    decision.contains("| accepted |")
+                }
+            };
+            assert_eq!(accepted, expected, "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_no_std() {
+        let output = Command::new(env!("CARGO"))
+            .args(["build", "--lib", "--no-default-features", "--target-dir", "target/no_std"])
+            .current_dir(env!("CARGO_MANIFEST_DIR"))
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    }
+
+    #[test]
+    fn test_canonical() {
+        let json = json::JSON::from_str(r#"{
+  "numbers": [333333333.33333329, 1E30, 4.50, 2e-3, 0.000000000000000000000000001],
+  "string": "\u20ac$\u000F\u000aA'\u0042\u0022\u005c\\\"\/",
+  "literals": [null, true, false]
+}"#).unwrap();
+        assert_eq!(json.to_canonical_string().unwrap(),
+            r#"{"literals":[null,true,false],"numbers":[333333333.3333333,1e+30,4.5,0.002,1e-27],"string":"€$\u000f\nA'B\"\\\\\"/"}"#);
+
+        let json = json::JSON::from_str(r#"{
+  "\u20ac": "Euro Sign",
+  "\r": "Carriage Return",
+  "\ufb33": "Hebrew Letter Dalet With Dagesh",
+  "1": "One",
+  "\ud83d\ude00": "Emoji: Grinning Face",
+  "\u0080": "Control",
+  "\u00f6": "Latin Small Letter O With Diaeresis"
+}"#).unwrap();
+        assert_eq!(json.to_canonical_string().unwrap(),
+            "{\"\\r\":\"Carriage Return\",\"1\":\"One\",\"\u{80}\":\"Control\",\"ö\":\"Latin Small Letter O With Diaeresis\",\
+\"€\":\"Euro Sign\",\"😀\":\"Emoji: Grinning Face\",\"\u{fb33}\":\"Hebrew Letter Dalet With Dagesh\"}");
+
+        let numbers = [
+            (0x0000000000000000, "0"),
+            (0x8000000000000000, "0"),
+            (0x0000000000000001, "5e-324"),
+            (0x8000000000000001, "-5e-324"),
+            (0x7fefffffffffffff, "1.7976931348623157e+308"),
+            (0xffefffffffffffff, "-1.7976931348623157e+308"),
+            (0x4340000000000000, "9007199254740992"),
+            (0xc340000000000000, "-9007199254740992"),
+            (0x4430000000000000, "295147905179352830000"),
+            (0x44b52d02c7e14af5, "9.999999999999997e+22"),
+            (0x44b52d02c7e14af6, "1e+23"),
+            (0x44b52d02c7e14af7, "1.0000000000000001e+23"),
+            (0x444b1ae4d6e2ef4e, "999999999999999700000"),
+            (0x444b1ae4d6e2ef4f, "999999999999999900000"),
+            (0x444b1ae4d6e2ef50, "1e+21"),
+            (0x3eb0c6f7a0b5ed8c, "9.999999999999997e-7"),
+            (0x3eb0c6f7a0b5ed8d, "0.000001"),
+            (0x41b3de4355555553, "333333333.3333332"),
+            (0x41b3de4355555554, "333333333.33333325"),
+            (0x41b3de4355555555, "333333333.3333333"),
+            (0x41b3de4355555556, "333333333.3333334"),
+            (0x41b3de4355555557, "333333333.33333343"),
+            (0xbecbf647612f3696, "-0.0000033333333333333333"),
+            (0x43143ff3c1cb0959, "1424953923781206.2")
+        ];
+        for (bits, expected) in numbers {
+            assert_eq!(json::JSON::Float(f64::from_bits(bits)).to_canonical_string().unwrap(), expected, "{:x}", bits);
+        }
+        for bits in [0x7fffffffffffffff, 0x7ff0000000000000, 0xfff0000000000000] {
+            assert!(json::JSON::Float(f64::from_bits(bits)).to_canonical_string().is_err());
+        }
+
+        assert_eq!(array![9007199254740993_i64, 18446744073709551615_u64, 7].to_canonical_string().unwrap(), "[9007199254740992,18446744073709552000,7]");
+        let exact = json::JSON::parse_exact_numbers("[1.50, 1e400]", Mode::Strict).unwrap();
+        assert_eq!(exact[0].to_canonical_string().unwrap(), "1.5");
+        assert!(exact.to_canonical_string().is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_writer() {
+        use crate::writer::{Escape, JSONWriter, NonFinite, WriteError};
+
+        let controls = json::JSON::from("\u{8}\u{c}\u{1}\t\"\\");
+        let expected = r#""\b\f\u0001\t\"\\""#;
+        let mut writer = JSONWriter::new(Vec::new());
+        writer.json(&controls).unwrap();
+        assert_eq!(String::from_utf8(writer.finish().unwrap()).unwrap(), expected);
+        assert_eq!(controls.to_string(), expected);
+        assert_eq!(controls.to_canonical_string().unwrap(), expected);
+
+        let mut writer = JSONWriter::new(Vec::new()).pretty(2);
+        writer.begin_object().unwrap();
+        writer.key("name").unwrap();
+        writer.value("a \"quoted\"\nline").unwrap();
+        writer.key("empty").unwrap();
+        writer.begin_array().unwrap();
+        writer.end_array().unwrap();
+        writer.key("rows").unwrap();
+        writer.begin_array().unwrap();
+        writer.value(1).unwrap();
+        writer.json(&array![true, json::JSON::Null]).unwrap();
+        writer.begin_object().unwrap();
+        writer.end_object().unwrap();
+        writer.end_array().unwrap();
+        writer.end_object().unwrap();
+        let pretty = String::from_utf8(writer.finish().unwrap()).unwrap();
+        assert_eq!(pretty, "{\n  \"name\": \"a \\\"quoted\\\"\\nline\",\n  \"empty\": [],\n  \"rows\": [\n    1,\n    [\n      true,\n      null\n    ],\n    {}\n  ]\n}");
+        assert_eq!(json::JSON::from_str(&pretty).unwrap().get("rows.1.0"), true);
+
+        let mut writer = JSONWriter::new(Vec::new());
+        assert!(matches!(writer.key("a"), Err(WriteError::Nesting(_))));
+        assert!(matches!(writer.end_object(), Err(WriteError::Nesting(_))));
+        writer.begin_object().unwrap();
+        assert!(matches!(writer.value(1), Err(WriteError::Nesting(_))));
+        assert!(matches!(writer.end_array(), Err(WriteError::Nesting(_))));
+        writer.key("a").unwrap();
+        assert!(matches!(writer.key("b"), Err(WriteError::Nesting(_))));
+        assert!(matches!(writer.end_object(), Err(WriteError::Nesting(_))));
+        assert!(matches!(writer.value(f64::NAN), Err(WriteError::NotFinite(_))));
+        writer.value(0.5).unwrap();
+        writer.end_object().unwrap();
+        assert!(matches!(writer.value(2), Err(WriteError::Nesting(_))));
+        assert_eq!(writer.finish().unwrap(), br#"{"a":0.5}"#);
+
+        let mut writer = JSONWriter::new(Vec::new());
+        writer.begin_array().unwrap();
+        assert!(matches!(writer.finish(), Err(WriteError::Nesting(_))));
+        assert!(matches!(JSONWriter::new(Vec::new()).finish(), Err(WriteError::Nesting(_))));
+
+        let text = "<a href=\"x\">&</a>\u{2028}é\u{1d11e}\u{7}";
+        for (escape, expected) in [
+            (Escape::Minimal, "\"<a href=\\\"x\\\">&</a>\u{2028}é\u{1d11e}\\u0007\""),
+            (Escape::ASCII, r#""<a href=\"x\">&</a>\u2028\u00e9\ud834\udd1e\u0007""#),
+            (Escape::HTML, "\"\\u003ca href=\\\"x\\\"\\u003e\\u0026\\u003c/a\\u003e\\u2028é\u{1d11e}\\u0007\"")
+        ] {
+            let mut writer = JSONWriter::new(Vec::new()).escape(escape);
+            writer.begin_object().unwrap();
+            writer.key(text).unwrap();
+            writer.value(text).unwrap();
+            writer.end_object().unwrap();
+            let written = String::from_utf8(writer.finish().unwrap()).unwrap();
+            assert_eq!(written, format!("{{{}:{}}}", expected, expected));
+            assert_eq!(json::JSON::from_str(&written).unwrap().get_obj().unwrap()[text], text);
+        }
+
+        for (non_finite, expected) in [(NonFinite::Null, "[null,null,null]"), (NonFinite::Literal, "[NaN,Infinity,-Infinity]")] {
+            let mut writer = JSONWriter::new(Vec::new()).non_finite(non_finite);
+            writer.json(&array![f64::NAN, f64::INFINITY, f64::NEG_INFINITY]).unwrap();
+            assert_eq!(writer.finish().unwrap(), expected.as_bytes());
+        }
+
+        let mut writer = JSONWriter::new(Vec::new());
+        writer.begin_array().unwrap();
+        for id in 0..100_000 {
+            writer.begin_object().unwrap();
+            writer.key("id").unwrap();
+            writer.value(id).unwrap();
+            writer.end_object().unwrap();
+        }
+        writer.end_array().unwrap();
+        let rows = json::JSON::from_str(std::str::from_utf8(&writer.finish().unwrap()).unwrap()).unwrap();
+        assert_eq!(rows.get("99999.id"), 99999);
+    }
+
+    #[test]
+    fn test_float_format() {
+        assert_eq!(json::JSON::Float(0.1).to_string(), "0.1");
+        assert_eq!(json::JSON::Float(0.1 + 0.2).to_string(), "0.30000000000000004");
+        assert_eq!(json::JSON::Float(100.0).to_string(), "100.0");
+        assert_eq!(json::JSON::Float(-0.0).to_string(), "-0.0");
+        assert_eq!(json::JSON::Float(1.5e-7).to_string(), "1.5e-7");
+        assert_eq!(json::JSON::Float(f64::MAX).to_string(), "1.7976931348623157e+308");
+        assert_eq!(json::JSON::Float(f64::NEG_INFINITY).to_string(), "null");
+
+        // xorshift64, so every run checks the same floats
+        let mut state = 0x2545f4914f6cdd1d_u64;
+        let mut checked = 0;
+        while checked < 20_000 {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            let float = f64::from_bits(state);
+            if !float.is_finite() {
+                continue
+            }
+
+            let written = json::JSON::Float(float).to_string();
+            match json::JSON::from_str(&written).unwrap() {
+                json::JSON::Float(read) => assert_eq!(read.to_bits(), float.to_bits(), "{}", written),
+                other => panic!("`{}` read back as {:?}", written, other)
+            }
+            let rust_digits = format!("{:e}", float).split('e').next().unwrap().replace(['-', '.'], "").len();
+            let our_digits = written.split('e').next().unwrap().replace(['-', '.'], "").trim_start_matches('0').trim_end_matches('0').len();
+            assert!(our_digits <= rust_digits.max(1), "{} is longer than {:e}", written, float);
+            checked += 1;
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_colored() {
+        use crate::color::ColorScheme;
+
+        let json = array![object!{"id" => 7}, "x", 1.5, false, json::JSON::Null, array![], f64::NAN];
+        let colored = json.to_colored_string(&ColorScheme::default());
+        assert_eq!(colored, concat!(
+            "\x1b[1;39m[\x1b[0m\n",
+            "  \x1b[1;39m{\x1b[0m\n",
+            "    \x1b[34;1m\"id\"\x1b[0m: \x1b[0;39m7\x1b[0m\n",
+            "  \x1b[1;39m}\x1b[0m,\n",
+            "  \x1b[0;32m\"x\"\x1b[0m,\n",
+            "  \x1b[0;39m1.5\x1b[0m,\n",
+            "  \x1b[0;39mfalse\x1b[0m,\n",
+            "  \x1b[1;30mnull\x1b[0m,\n",
+            "  \x1b[1;39m[\x1b[0m\x1b[1;39m]\x1b[0m,\n",
+            "  \x1b[1;30mnull\x1b[0m\n",
+            "\x1b[1;39m]\x1b[0m"
+        ));
+
+        let plain = json.to_colored_string(&ColorScheme::none());
+        assert!(!plain.contains('\x1b'));
+        assert_eq!(plain.replace(['\n', ' '], ""), r#"[{"id":7},"x",1.5,false,null,[],null]"#);
+        assert_eq!(plain.replace(['\n', ' '], ""), json.to_string());
+    }
+
+    #[test]
+    fn test_mutation() {
+        use crate::json::TypeError;
+
+        let mut doc = object!{"users" => array![], "count" => 0};
+        doc.get_mut("users").unwrap().unwrap().push(object!{"name" => "ann", "tags" => array!["a"]}).unwrap();
+        doc.get_mut("users").unwrap().unwrap().push(object!{"name" => "bob"}).unwrap();
+        assert_eq!(doc.insert("count", 2), Ok(Some(0.into())));
+        assert_eq!(doc.insert("updated", true), Ok(None));
+        assert_eq!(doc.get("users.1.name"), "bob");
+
+        let users = doc.get_mut("users").unwrap().unwrap();
+        assert_eq!(users.pop(), Ok(Some(object!{"name" => "bob"})));
+        users.push(json::JSON::Null).unwrap();
+        users.retain(|user| !user.is_null()).unwrap();
+        assert_eq!(*users, array![object!{"name" => "ann", "tags" => array!["a"]}]);
+        assert_eq!(users.remove("name"), Err(TypeError::ExpectedObject));
+
+        let tags = doc.entry("totals").unwrap().or_insert_with(|| object!{}.into());
+        tags.entry("a").unwrap().and_modify(|total| *total = 5.into()).or_insert(1);
+        tags.entry("a").unwrap().and_modify(|total| *total = 5.into()).or_insert(1);
+        assert_eq!(tags.entry("b").unwrap().key(), "b");
+        assert_eq!(doc["totals"], object!{"a" => 5});
+
+        let users = doc.get_mut("users").unwrap().unwrap().take();
+        assert_eq!(users.get("0.name"), "ann");
+        assert!(doc["users"].is_null());
+        assert_eq!(doc.remove("users"), Ok(Some(json::JSON::Null)));
+        assert_eq!(doc.remove("missing"), Ok(None));
+        assert_eq!(doc, object!{"count" => 2, "updated" => true, "totals" => object!{"a" => 5}});
+
+        let mut scalar = json::JSON::from(1);
+        assert_eq!(scalar.pop(), Err(TypeError::ExpectedArray));
+        assert_eq!(scalar.get_mut("a"), Err(TypeError::ExpectedObject));
+        assert_eq!(scalar.retain(|_| false), Err(TypeError::ExpectedContainer));
+        assert_eq!(scalar.push(2), Err(TypeError::ExpectedArray));
+        assert_eq!(scalar.insert("a", 2), Err(TypeError::ExpectedObject));
+        assert!(scalar.entry("a").is_err());
+        assert_eq!(scalar, 1);
+        assert_eq!(TypeError::ExpectedContainer.to_string(), "Expected Array or Object!");
+    }
+
+    #[test]
+    fn test_iteration() {
+        let mut doc = object!{"items" => array![1, 2, 3], "name" => "héllo", "meta" => object!{"a" => 1, "b" => 2}};
+        assert_eq!(doc.len(), 3);
+        assert_eq!(doc["items"].len(), 3);
+        assert_eq!(doc["name"].len(), 6);
+        assert!(!doc.is_empty());
+        assert!(json::JSON::from(array![]).is_empty());
+        assert!(json::JSON::from(7).is_empty());
+        assert!(!json::JSON::from(" ").is_empty());
+
+        assert_eq!(doc["items"].members().collect::<Vec<_>>(), [&json::JSON::from(1), &2.into(), &3.into()]);
+        assert_eq!(doc["meta"].members().count(), 0);
+        for item in doc.get_mut("items").unwrap().unwrap().members_mut() {
+            *item = (item.get_int().unwrap() * 10).into();
+        }
+        for item in doc.get_mut("items").unwrap().unwrap() {
+            *item = (item.get_int().unwrap() + 1).into();
+        }
+        assert_eq!(doc["items"], array![11, 21, 31]);
+
+        let mut keys: Vec<&str> = doc["meta"].entries().map(|(key, _)| key).collect();
+        keys.sort_unstable();
+        assert_eq!(keys, ["a", "b"]);
+        assert_eq!(doc["items"].entries().count(), 0);
+        for (key, value) in doc.get_mut("meta").unwrap().unwrap().entries_mut() {
+            *value = key.into();
+        }
+        assert_eq!(doc["meta"], object!{"a" => "a", "b" => "b"});
+
+        let total: i64 = (&doc["items"]).into_iter().filter_map(|item| item.get_int()).sum();
+        assert_eq!(total, 63);
+        let owned: Vec<json::JSON> = doc.remove("items").unwrap().unwrap().into_iter().collect();
+        assert_eq!(owned, [11, 21, 31]);
+        for value in &mut doc {
+            if let Some(string) = value.get_string() {
+                *value = string.to_uppercase().into();
+            }
+        }
+        assert_eq!((&doc).into_iter().count(), 2);
+        let mut values: Vec<json::JSON> = doc.into_iter().collect();
+        values.sort_by_key(|value| value.get_string().is_some());
+        assert_eq!(values, [object!{"a" => "a", "b" => "b"}, json::JSON::from("HÉLLO")]);
+        assert_eq!(json::JSON::from(7).into_iter().count(), 0);
+    }
+
+    #[test]
+    fn test_walk() {
+        use crate::visit::{Segment, Visitor, Walk};
+
+        struct Counter {
+            nodes: usize,
+            deepest: Vec<String>,
+        }
+
+        impl Visitor for Counter {
+            fn visit(&mut self, path: &[Segment<'_>], _: &json::JSON) -> Walk {
+                self.nodes += 1;
+                if path.len() > self.deepest.len() {
+                    self.deepest = path.iter().map(|segment| format!("{:?}", segment)).collect();
+                }
+                Walk::Continue
+            }
+        }
+
+        let mut doc = array![1, object!{"a" => array![true, object!{"b" => json::JSON::Null}]}, "x"];
+        let mut counter = Counter { nodes: 0, deepest: Vec::new() };
+        assert!(doc.walk(&mut counter));
+        assert_eq!(counter.nodes, 8);
+        assert_eq!(counter.deepest, ["Index(1)", "Key(\"a\")", "Index(1)", "Key(\"b\")"]);
+
+        let mut seen = Vec::new();
+        let finished = doc.walk(&mut |path: &[Segment], value: &json::JSON| {
+            seen.push(path.len());
+            match value {
+                json::JSON::Object(_) => Walk::Skip,
+                json::JSON::String(_) => Walk::Stop,
+                _ => Walk::Continue
+            }
+        });
+        assert!(!finished);
+        assert_eq!(seen, [0, 1, 1, 1]);
+
+        let mut visits = 0;
+        assert!(!doc.walk(&mut |_: &[Segment], _: &json::JSON| {
+            visits += 1;
+            if visits == 3 { Walk::Stop } else { Walk::Continue }
+        }));
+        assert_eq!(visits, 3);
+
+        assert!(doc.walk_mut(&mut |path: &[Segment], value: &mut json::JSON| {
+            match (path.last(), value.get_int()) {
+                (Some(Segment::Index(0)), Some(int_val)) => *value = (int_val * 2).into(),
+                (Some(Segment::Key("b")), _) => *value = array![0],
+                _ => {}
+            }
+            Walk::Continue
+        }));
+        assert_eq!(doc, array![2, object!{"a" => array![true, object!{"b" => array![0]}]}, "x"]);
+    }
+
+    #[test]
+    fn test_pointer() {
+        use crate::pointer;
+        use crate::visit::{Segment, Walk};
+
+        // The example document of RFC 6901, section 5
+        let rfc = json::JSON::from_str(r#"{
+            "foo": ["bar", "baz"],
+            "": 0,
+            "a/b": 1,
+            "c%d": 2,
+            "e^f": 3,
+            "g|h": 4,
+            "i\\j": 5,
+            "k\"l": 6,
+            " ": 7,
+            "m~n": 8
+        }"#).unwrap();
+        assert_eq!(rfc.pointer(""), Some(&rfc));
+        assert_eq!(*rfc.pointer("/foo").unwrap(), array!["bar", "baz"]);
+        let expected = [("/foo/0", "bar".into()), ("/", 0.into()), ("/a~1b", 1.into()), ("/c%d", 2.into()), ("/e^f", 3.into()),
+            ("/g|h", 4.into()), ("/i\\j", 5.into()), ("/k\"l", 6.into()), ("/ ", 7.into()), ("/m~0n", 8.into())];
+        for (path, value) in expected {
+            assert_eq!(rfc.pointer(path), Some(&value), "{}", path);
+        }
+
+        let doc = object!{"10" => "key", "list" => array![0, 1], "a.b" => object!{"c" => true}};
+        assert_eq!(*doc.pointer("/10").unwrap(), "key");
+        assert_eq!(*doc.pointer("/a.b/c").unwrap(), true);
+        for invalid in ["list", "/list/01", "/list/-", "/list/2", "/list/+1", "/m~2n", "/m~", "/list/0/x"] {
+            assert_eq!(doc.pointer(invalid), None, "{}", invalid);
+        }
+
+        let mut paths = Vec::new();
+        rfc.walk(&mut |path: &[Segment], value: &json::JSON| {
+            paths.push((pointer::from_path(path), value.clone()));
+            Walk::Continue
+        });
+        assert_eq!(paths.len(), 13);
+        for (path, value) in paths {
+            assert_eq!(rfc.pointer(&path), Some(&value), "{}", path);
+        }
+        assert_eq!(pointer::escape("a~/b"), "a~0~1b");
+        assert!(matches!(pointer::escape("plain"), Cow::Borrowed("plain")));
+
+        let mut doc = doc;
+        *doc.pointer_mut("/list/0").unwrap() = "zero".into();
+        doc.pointer_mut("/a.b").unwrap().insert("d", 1).unwrap();
+        assert_eq!(doc.pointer_mut("/list/9"), None);
+        assert_eq!(doc, object!{"10" => "key", "list" => array!["zero", 1], "a.b" => object!{"c" => true, "d" => 1}});
+    }
+
+    #[test]
+    fn test_patch() {
+        use crate::patch::PatchError;
+
+        // Examples from RFC 6902, appendix A: document, patch, result
+        let examples = [
+            (r#"{"foo": "bar"}"#, r#"[{"op": "add", "path": "/baz", "value": "qux"}]"#, r#"{"baz": "qux", "foo": "bar"}"#),
+            (r#"{"foo": ["bar", "baz"]}"#, r#"[{"op": "add", "path": "/foo/1", "value": "qux"}]"#, r#"{"foo": ["bar", "qux", "baz"]}"#),
+            (r#"{"baz": "qux", "foo": "bar"}"#, r#"[{"op": "remove", "path": "/baz"}]"#, r#"{"foo": "bar"}"#),
+            (r#"{"foo": ["bar", "qux", "baz"]}"#, r#"[{"op": "remove", "path": "/foo/1"}]"#, r#"{"foo": ["bar", "baz"]}"#),
+            (r#"{"baz": "qux", "foo": "bar"}"#, r#"[{"op": "replace", "path": "/baz", "value": "boo"}]"#, r#"{"baz": "boo", "foo": "bar"}"#),
+            (r#"{"foo": {"bar": "baz", "waldo": "fred"}, "qux": {"corge": "grault"}}"#, r#"[{"op": "move", "from": "/foo/waldo", "path": "/qux/thud"}]"#,
+                r#"{"foo": {"bar": "baz"}, "qux": {"corge": "grault", "thud": "fred"}}"#),
+            (r#"{"foo": ["all", "grass", "cows", "eat"]}"#, r#"[{"op": "move", "from": "/foo/1", "path": "/foo/3"}]"#, r#"{"foo": ["all", "cows", "eat", "grass"]}"#),
+            (r#"{"baz": "qux", "foo": ["a", 2, "c"]}"#, r#"[{"op": "test", "path": "/baz", "value": "qux"}, {"op": "test", "path": "/foo/1", "value": 2.0}]"#,
+                r#"{"baz": "qux", "foo": ["a", 2, "c"]}"#),
+            (r#"{"foo": "bar"}"#, r#"[{"op": "add", "path": "/child", "value": {"grandchild": {}}}]"#, r#"{"foo": "bar", "child": {"grandchild": {}}}"#),
+            (r#"{"foo": "bar"}"#, r#"[{"op": "add", "path": "/baz", "value": "qux", "xyz": 123}]"#, r#"{"foo": "bar", "baz": "qux"}"#),
+            (r#"{"/": 9, "~1": 10}"#, r#"[{"op": "test", "path": "/~01", "value": 10}]"#, r#"{"/": 9, "~1": 10}"#),
+            (r#"{"foo": ["bar"]}"#, r#"[{"op": "add", "path": "/foo/-", "value": ["abc", "def"]}]"#, r#"{"foo": ["bar", ["abc", "def"]]}"#),
+            (r#"{"a": {"b": 1}}"#, r#"[{"op": "copy", "from": "/a", "path": "/c"}, {"op": "replace", "path": "/c/b", "value": 2}]"#, r#"{"a": {"b": 1}, "c": {"b": 2}}"#),
+            (r#"[1]"#, r#"[{"op": "add", "path": "", "value": {"whole": true}}]"#, r#"{"whole": true}"#)
+        ];
+        for (document, patch, result) in examples {
+            let mut json = json::JSON::from_str(document).unwrap();
+            json.apply_patch(&json::JSON::from_str(patch).unwrap()).unwrap();
+            assert_eq!(json, json::JSON::from_str(result).unwrap(), "{}", patch);
+        }
+
+        let document = json::JSON::from_str(r#"{"foo": "bar", "list": [1, 2], "q": {"bar": 2}}"#).unwrap();
+        let errors = [
+            (r#"{"op": "add", "path": "/baz/bat", "value": "qux"}"#, "NotFound"),
+            (r#"{"op": "add", "path": "/list/3", "value": 3}"#, "NotFound"),
+            (r#"{"op": "add", "path": "/list/01", "value": 3}"#, "NotFound"),
+            (r#"{"op": "remove", "path": "/missing"}"#, "NotFound"),
+            (r#"{"op": "replace", "path": "/list/2", "value": 3}"#, "NotFound"),
+            (r#"{"op": "copy", "from": "/nothing", "path": "/x"}"#, "NotFound"),
+            (r#"{"op": "test", "path": "/q", "value": {"bar": "2"}}"#, "TestFailed"),
+            (r#"{"op": "test", "path": "/list", "value": [1]}"#, "TestFailed"),
+            (r#"{"op": "add", "path": "/baz"}"#, "Invalid"),
+            (r#"{"op": "jump", "path": "/foo"}"#, "Invalid"),
+            (r#"{"path": "/foo"}"#, "Invalid"),
+            (r#"{"op": "remove", "path": "foo"}"#, "Invalid"),
+            (r#"{"op": "remove", "path": ""}"#, "Invalid"),
+            (r#"{"op": "move", "from": "/q", "path": "/q/bar/x"}"#, "Invalid"),
+            (r#""add""#, "Invalid")
+        ];
+        for (operation, kind) in errors {
+            // Each failing operation comes after one that works, which has to be undone
+            let patch = json::JSON::from_str(&format!(r#"[{{"op": "add", "path": "/new", "value": 1}}, {}]"#, operation)).unwrap();
+            let mut json = document.clone();
+            let error = json.apply_patch(&patch).unwrap_err();
+            assert_eq!(format!("{:?}", error).split('(').next().unwrap(), kind, "{}", operation);
+            assert_eq!(json, document, "{}", operation);
+        }
+        assert!(matches!(document.clone().apply_patch(&object!{"op" => "add"}), Err(PatchError::Invalid(_))));
+        assert_eq!(PatchError::NotFound("/a".to_string()).to_string(), "Nothing at `/a`");
+    }
 }
@@ -0,0 +1,185 @@
+//! # Visit
+//!
+//! Walks every value of a document depth first, telling a visitor where each one is.
+
+use crate::prelude::*;
+use crate::json::JSON;
+
+/// One step of the path from the root to a value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Segment<'a> {
+    /// A member of an object
+    Key(&'a str),
+
+    /// An item of an array
+    Index(usize),
+}
+
+/// What the walk does after a visitor has seen a value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Walk {
+    /// Go on into the value's children, then its siblings
+    Continue,
+
+    /// Leave out the value's children and go on to its siblings
+    Skip,
+
+    /// End the walk
+    Stop,
+}
+
+/// Sees each value of a document, parents before their children. `path` leads from the root
+/// to `value` and is empty for the root itself.
+///
+/// Closures taking `(&[Segment], &JSON)` are visitors too.
+pub trait Visitor {
+    fn visit(&mut self, path: &[Segment<'_>], value: &JSON) -> Walk;
+}
+
+/// Sees each value of a document and can change it. Children are walked after the visitor
+/// returns, so any the visitor adds are visited as well.
+///
+/// Closures taking `(&[Segment], &mut JSON)` are visitors too.
+pub trait VisitorMut {
+    fn visit_mut(&mut self, path: &[Segment<'_>], value: &mut JSON) -> Walk;
+}
+
+impl<F> Visitor for F
+where
+    F: FnMut(&[Segment<'_>], &JSON) -> Walk
+{
+    fn visit(&mut self, path: &[Segment<'_>], value: &JSON) -> Walk {
+        self(path, value)
+    }
+}
+
+impl<F> VisitorMut for F
+where
+    F: FnMut(&[Segment<'_>], &mut JSON) -> Walk
+{
+    fn visit_mut(&mut self, path: &[Segment<'_>], value: &mut JSON) -> Walk {
+        self(path, value)
+    }
+}
+
+impl JSON {
+    /// Shows `visitor` every value in the document, depth first. Objects are walked in their map's order.
+    /// Returns `false` if the visitor stopped the walk early.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use json_parser::{array, object};
+    /// # use json_parser::json::JSON;
+    /// # use json_parser::visit::{Segment, Walk};
+    /// let json = object!{"links" => array!["https://a.com", "ftp://b.org"], "secret" => object!{"url" => "https://c.net"}};
+    ///
+    /// let mut urls = Vec::new();
+    /// json.walk(&mut |path: &[Segment], value: &JSON| {
+    ///     if path.first() == Some(&Segment::Key("secret")) {
+    ///         return Walk::Skip
+    ///     }
+    ///     if let JSON::String(string) = value {
+    ///         if string.starts_with("https://") {
+    ///             urls.push(string.clone());
+    ///         }
+    ///     }
+    ///     Walk::Continue
+    /// });
+    /// assert_eq!(urls, ["https://a.com"]);
+    /// ```
+    pub fn walk<V: Visitor>(&self, visitor: &mut V) -> bool {
+        walk(self, &mut Vec::new(), visitor)
+    }
+
+    /// Shows `visitor` every value in the document to change, depth first.
+    /// Returns `false` if the visitor stopped the walk early.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use json_parser::{array, object};
+    /// # use json_parser::json::JSON;
+    /// # use json_parser::visit::{Segment, Walk};
+    /// let mut json = object!{"users" => array![object!{"name" => "ann", "password" => "hunter2"}]};
+    /// json.walk_mut(&mut |path: &[Segment], value: &mut JSON| {
+    ///     if path.last() == Some(&Segment::Key("password")) {
+    ///         *value = "***".into();
+    ///     }
+    ///     Walk::Continue
+    /// });
+    /// assert_eq!(json.get("users.0.password"), "***");
+    /// ```
+    pub fn walk_mut<V: VisitorMut>(&mut self, visitor: &mut V) -> bool {
+        walk_mut(self, &mut Vec::new(), visitor)
+    }
+}
+
+/// Visits `value` and then its children, returning `false` once the visitor has stopped
+fn walk<'a, V: Visitor>(value: &'a JSON, path: &mut Vec<Segment<'a>>, visitor: &mut V) -> bool {
+    match visitor.visit(path, value) {
+        Walk::Continue => {},
+        Walk::Skip => return true,
+        Walk::Stop => return false
+    }
+
+    match *value {
+        JSON::Array(ref json_arr) => {
+            for (index, item) in json_arr.iter().enumerate() {
+                path.push(Segment::Index(index));
+                let going = walk(item, path, visitor);
+                path.pop();
+                if !going {
+                    return false
+                }
+            }
+        },
+        JSON::Object(ref json_obj) => {
+            for (key, item) in json_obj.iter() {
+                path.push(Segment::Key(key));
+                let going = walk(item, path, visitor);
+                path.pop();
+                if !going {
+                    return false
+                }
+            }
+        },
+        _ => {}
+    }
+
+    true
+}
+
+fn walk_mut<'a, V: VisitorMut>(value: &'a mut JSON, path: &mut Vec<Segment<'a>>, visitor: &mut V) -> bool {
+    match visitor.visit_mut(path, value) {
+        Walk::Continue => {},
+        Walk::Skip => return true,
+        Walk::Stop => return false
+    }
+
+    match *value {
+        JSON::Array(ref mut json_arr) => {
+            for (index, item) in json_arr.iter_mut().enumerate() {
+                path.push(Segment::Index(index));
+                let going = walk_mut(item, path, visitor);
+                path.pop();
+                if !going {
+                    return false
+                }
+            }
+        },
+        JSON::Object(ref mut json_obj) => {
+            for (key, item) in json_obj.iter_mut() {
+                path.push(Segment::Key(key));
+                let going = walk_mut(item, path, visitor);
+                path.pop();
+                if !going {
+                    return false
+                }
+            }
+        },
+        _ => {}
+    }
+
+    true
+}
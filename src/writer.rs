@@ -0,0 +1,332 @@
+//! # JSONWriter
+//!
+//! Writes JSON straight to an `io::Write` one call at a time, for output too big to build as a
+//! `JSON` value first.
+
+use std::fmt;
+use std::io::{self, Write};
+
+use crate::color::ColorScheme;
+use crate::escape;
+use crate::json::JSON;
+
+pub use crate::escape::Escape;
+
+/// Writes a document piece by piece, checking that every call fits where it's made
+///
+/// # Example
+///
+/// ```
+/// # use json_parser::{array, json::JSON};
+/// # use json_parser::writer::JSONWriter;
+/// let mut writer = JSONWriter::new(Vec::new());
+/// writer.begin_object().unwrap();
+/// writer.key("rows").unwrap();
+/// writer.begin_array().unwrap();
+/// for id in 0..3 {
+///     writer.value(id).unwrap();
+/// }
+/// writer.end_array().unwrap();
+/// writer.key("totals").unwrap();
+/// writer.json(&array![1.5, "two"]).unwrap();
+/// assert!(writer.value(4).is_err());
+/// writer.end_object().unwrap();
+///
+/// let bytes = writer.finish().unwrap();
+/// assert_eq!(String::from_utf8(bytes).unwrap(), r#"{"rows":[0,1,2],"totals":[1.5,"two"]}"#);
+/// ```
+pub struct JSONWriter<W: Write> {
+    writer: W,
+    indent: Option<usize>,
+    non_finite: NonFinite,
+    escape: Escape,
+    colors: Option<ColorScheme>,
+    stack: Vec<Frame>,
+    finished_root: bool,
+}
+
+/// An array or object that is still open
+#[derive(Debug, Clone, Copy)]
+enum Frame {
+    Array { empty: bool },
+    Object { empty: bool, has_key: bool },
+}
+
+/// What to write for a `NaN` or infinity, which JSON has no way to represent
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NonFinite {
+    /// Fail with `WriteError::NotFinite`, the default
+    Error,
+
+    /// Write `null`, the same as JavaScript's `JSON.stringify`
+    Null,
+
+    /// Write `NaN`, `Infinity` or `-Infinity` like JSON5 does, which strict parsers reject
+    Literal,
+}
+
+#[derive(Debug)]
+pub enum WriteError {
+    /// The underlying writer failed
+    Io(io::Error),
+
+    /// A call that doesn't fit where the writer is, like a key inside an array
+    Nesting(String),
+
+    /// A `NaN` or infinity, which JSON can't represent
+    NotFinite(f64),
+}
+
+impl<W: Write> JSONWriter<W> {
+    /// Makes a writer that writes compactly, with no whitespace between tokens
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            indent: None,
+            non_finite: NonFinite::Error,
+            escape: Escape::Minimal,
+            colors: None,
+            stack: Vec::new(),
+            finished_root: false
+        }
+    }
+
+    /// Puts every array item and object member on its own line, indented by `indent` spaces per level
+    pub fn pretty(mut self, indent: usize) -> Self {
+        self.indent = Some(indent);
+        self
+    }
+
+    /// Sets what `NaN` and infinities are written as, by default they're an error
+    pub fn non_finite(mut self, non_finite: NonFinite) -> Self {
+        self.non_finite = non_finite;
+        self
+    }
+
+    /// Sets which characters of strings and keys are escaped, by default only the ones JSON requires
+    ///
+    /// ```
+    /// # use json_parser::writer::{Escape, JSONWriter};
+    /// let mut writer = JSONWriter::new(Vec::new()).escape(Escape::ASCII);
+    /// writer.value("é 🎉").unwrap();
+    /// assert_eq!(writer.finish().unwrap(), br#""\u00e9 \ud83c\udf89""#);
+    /// ```
+    pub fn escape(mut self, escape: Escape) -> Self {
+        self.escape = escape;
+        self
+    }
+
+    /// Colors keys, values and brackets with ANSI escape codes, for printing to a terminal
+    pub fn colors(mut self, colors: ColorScheme) -> Self {
+        self.colors = Some(colors);
+        self
+    }
+
+    pub fn begin_object(&mut self) -> Result<(), WriteError> {
+        self.before_value()?;
+        self.stack.push(Frame::Object { empty: true, has_key: false });
+        self.paint(|colors| &colors.bracket, "{")
+    }
+
+    pub fn end_object(&mut self) -> Result<(), WriteError> {
+        match self.stack.last() {
+            Some(Frame::Object { has_key: false, empty }) => {
+                let empty = *empty;
+                self.stack.pop();
+                self.close("}", empty)
+            },
+            Some(Frame::Object { .. }) => Err(WriteError::Nesting("Expected a value for the last key before `}`".to_string())),
+            _ => Err(WriteError::Nesting("`}` without an open object".to_string()))
+        }
+    }
+
+    pub fn begin_array(&mut self) -> Result<(), WriteError> {
+        self.before_value()?;
+        self.stack.push(Frame::Array { empty: true });
+        self.paint(|colors| &colors.bracket, "[")
+    }
+
+    pub fn end_array(&mut self) -> Result<(), WriteError> {
+        match self.stack.last() {
+            Some(Frame::Array { empty }) => {
+                let empty = *empty;
+                self.stack.pop();
+                self.close("]", empty)
+            },
+            _ => Err(WriteError::Nesting("`]` without an open array".to_string()))
+        }
+    }
+
+    /// Writes the key of the next object member, which has to be followed by its value
+    pub fn key(&mut self, key: &str) -> Result<(), WriteError> {
+        let empty = match self.stack.last_mut() {
+            Some(Frame::Object { empty, has_key: has_key @ false }) => {
+                *has_key = true;
+                std::mem::replace(empty, false)
+            },
+            Some(Frame::Object { .. }) => return Err(WriteError::Nesting(format!("Expected a value before the key `{}`", key))),
+            _ => return Err(WriteError::Nesting(format!("Key `{}` outside of an object", key)))
+        };
+
+        if !empty {
+            self.write(b",")?;
+        }
+        self.newline()?;
+        self.write_string(key, |colors| &colors.key)?;
+        match self.indent {
+            Some(_) => self.write(b": "),
+            None => self.write(b":")
+        }
+    }
+
+    /// Writes a string, number, boolean or null, or a whole `JSON` value
+    pub fn value<V: Into<JSON>>(&mut self, value: V) -> Result<(), WriteError> {
+        self.json(&value.into())
+    }
+
+    /// Writes an existing `JSON` value where the next value goes, laid out like the rest of the output
+    pub fn json(&mut self, json: &JSON) -> Result<(), WriteError> {
+        match *json {
+            JSON::Array(ref json_arr) => {
+                self.begin_array()?;
+                for item in json_arr.iter() {
+                    self.json(item)?;
+                }
+                self.end_array()
+            },
+            JSON::Object(ref json_obj) => {
+                self.begin_object()?;
+                for (key, value) in json_obj.iter() {
+                    self.key(key)?;
+                    self.json(value)?;
+                }
+                self.end_object()
+            },
+            JSON::Float(float) if !float.is_finite() => {
+                let literal = match self.non_finite {
+                    NonFinite::Error => return Err(WriteError::NotFinite(float)),
+                    NonFinite::Null => "null",
+                    NonFinite::Literal if float.is_nan() => "NaN",
+                    NonFinite::Literal if float > 0.0 => "Infinity",
+                    NonFinite::Literal => "-Infinity"
+                };
+                self.before_value()?;
+                match self.non_finite {
+                    NonFinite::Null => self.paint(|colors| &colors.null, literal)?,
+                    _ => self.paint(|colors| &colors.number, literal)?
+                }
+                self.after_value()
+            },
+            JSON::String(ref string) => {
+                self.before_value()?;
+                self.write_string(string, |colors| &colors.string)?;
+                self.after_value()
+            },
+            JSON::Boolean(_) => {
+                self.before_value()?;
+                self.paint(|colors| &colors.boolean, &json.to_string())?;
+                self.after_value()
+            },
+            JSON::Null => {
+                self.before_value()?;
+                self.paint(|colors| &colors.null, "null")?;
+                self.after_value()
+            },
+            _ => {
+                self.before_value()?;
+                self.paint(|colors| &colors.number, &json.to_string())?;
+                self.after_value()
+            }
+        }
+    }
+
+    /// Checks the document is complete, flushes it, and gives back the underlying writer
+    pub fn finish(mut self) -> Result<W, WriteError> {
+        match self.stack.last() {
+            Some(Frame::Array { .. }) => return Err(WriteError::Nesting("Unclosed array".to_string())),
+            Some(Frame::Object { .. }) => return Err(WriteError::Nesting("Unclosed object".to_string())),
+            None if !self.finished_root => return Err(WriteError::Nesting("Nothing was written".to_string())),
+            None => {}
+        }
+
+        self.writer.flush().map_err(WriteError::Io)?;
+        Ok(self.writer)
+    }
+
+    /// Writes the comma and line break a new value needs, or fails if a value can't go here
+    fn before_value(&mut self) -> Result<(), WriteError> {
+        let empty = match self.stack.last_mut() {
+            None if self.finished_root => return Err(WriteError::Nesting("Only one top level value can be written".to_string())),
+            None => return Ok(()),
+            Some(Frame::Object { has_key, .. }) => {
+                if !*has_key {
+                    return Err(WriteError::Nesting("Expected a key before an object value".to_string()))
+                }
+                *has_key = false;
+                return Ok(())
+            },
+            Some(Frame::Array { empty }) => std::mem::replace(empty, false)
+        };
+
+        if !empty {
+            self.write(b",")?;
+        }
+        self.newline()
+    }
+
+    fn after_value(&mut self) -> Result<(), WriteError> {
+        if self.stack.is_empty() {
+            self.finished_root = true;
+        }
+
+        Ok(())
+    }
+
+    fn close(&mut self, bracket: &str, empty: bool) -> Result<(), WriteError> {
+        if !empty {
+            self.newline()?;
+        }
+        self.paint(|colors| &colors.bracket, bracket)?;
+        self.after_value()
+    }
+
+    /// Starts a new line indented to the current depth, when pretty printing
+    fn newline(&mut self) -> Result<(), WriteError> {
+        if let Some(indent) = self.indent {
+            let width = indent * self.stack.len();
+            write!(self.writer, "\n{:width$}", "", width = width).map_err(WriteError::Io)?;
+        }
+
+        Ok(())
+    }
+
+    fn write_string(&mut self, string: &str, color: fn(&ColorScheme) -> &String) -> Result<(), WriteError> {
+        let mut escaped = String::with_capacity(string.len() + 2);
+        escape::write(&mut escaped, string, self.escape).expect("writing to a String can't fail");
+        self.paint(color, &escaped)
+    }
+
+    /// Writes `text` in the color `color` picks from the scheme, or plainly when there are no colors
+    fn paint(&mut self, color: fn(&ColorScheme) -> &String, text: &str) -> Result<(), WriteError> {
+        match self.colors.as_ref().map(color) {
+            Some(color) if !color.is_empty() => write!(self.writer, "\x1b[{}m{}\x1b[0m", color, text),
+            _ => self.writer.write_all(text.as_bytes())
+        }.map_err(WriteError::Io)
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> Result<(), WriteError> {
+        self.writer.write_all(bytes).map_err(WriteError::Io)
+    }
+}
+
+impl fmt::Display for WriteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            WriteError::Io(ref err) => write!(f, "Could not write JSON: {}", err),
+            WriteError::Nesting(ref msg) => write!(f, "{}", msg),
+            WriteError::NotFinite(float) => write!(f, "`{}` can't be written as JSON", float)
+        }
+    }
+}
+
+impl std::error::Error for WriteError {}